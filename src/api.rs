@@ -0,0 +1,387 @@
+use std::future::Future;
+
+use bytes::Bytes;
+use chrono_tz::Tz;
+
+#[cfg(feature = "tags")]
+use crate::models::TagList;
+#[cfg(feature = "chaos")]
+use crate::models::{ChaosTriggersConfiguration, ChaosTriggersResponse};
+#[cfg(feature = "checks")]
+use crate::models::{HtmlCheckResponse, LinkCheckResponse, SpamAssassinResponse};
+#[cfg(feature = "send")]
+use crate::models::{SendMessage, SendMessageResponse};
+use crate::{
+    MailpitClient,
+    error::Error,
+    models::{
+        ApplicationInformation, EmbedMode, MessageHeaders, MessageSummary, MessagesSummary,
+        WebUIConfiguration,
+    },
+};
+
+/// The Mailpit REST API, implemented by [`MailpitClient`].
+///
+/// Code depending on this trait instead of [`MailpitClient`] can be tested
+/// with a fake implementation, without running Mailpit. The trait covers
+/// the endpoints only, convenience helpers built on top of them like
+/// [`MailpitClient::drain`] stay on [`MailpitClient`]. See the methods of
+/// [`MailpitClient`] for the documentation of each endpoint.
+pub trait MailpitApi {
+    /// See [`MailpitClient::get_application_information`].
+    fn get_application_information(
+        &self,
+    ) -> impl Future<Output = Result<ApplicationInformation, Error>> + Send;
+
+    /// See [`MailpitClient::get_webui_configuration`].
+    fn get_webui_configuration(
+        &self,
+    ) -> impl Future<Output = Result<WebUIConfiguration, Error>> + Send;
+
+    /// See [`MailpitClient::get_message_summary`].
+    fn get_message_summary(
+        &self,
+        id: &str,
+    ) -> impl Future<Output = Result<MessageSummary, Error>> + Send;
+
+    /// See [`MailpitClient::get_message_headers`].
+    fn get_message_headers(
+        &self,
+        id: &str,
+    ) -> impl Future<Output = Result<MessageHeaders, Error>> + Send;
+
+    /// See [`MailpitClient::get_message_attachment`].
+    fn get_message_attachment(
+        &self,
+        id: &str,
+        part_id: &str,
+    ) -> impl Future<Output = Result<Bytes, Error>> + Send;
+
+    /// See [`MailpitClient::get_message_attachment_image_thumbnail`].
+    fn get_message_attachment_image_thumbnail(
+        &self,
+        id: &str,
+        part_id: &str,
+    ) -> impl Future<Output = Result<Bytes, Error>> + Send;
+
+    /// See [`MailpitClient::get_message_source`].
+    fn get_message_source(&self, id: &str) -> impl Future<Output = Result<String, Error>> + Send;
+
+    #[cfg(feature = "send")]
+    /// See [`MailpitClient::post_release_message`].
+    fn post_release_message(
+        &self,
+        id: &str,
+        to: &[&str],
+    ) -> impl Future<Output = Result<bool, Error>> + Send;
+
+    #[cfg(feature = "send")]
+    /// See [`MailpitClient::post_send_message`].
+    fn post_send_message(
+        &self,
+        message: SendMessage,
+    ) -> impl Future<Output = Result<SendMessageResponse, Error>> + Send;
+
+    /// See [`MailpitClient::get_list_messages`].
+    fn get_list_messages(
+        &self,
+        start: Option<usize>,
+        limit: Option<usize>,
+    ) -> impl Future<Output = Result<MessagesSummary, Error>> + Send;
+
+    /// See [`MailpitClient::put_set_read_status`].
+    fn put_set_read_status(
+        &self,
+        read: Option<bool>,
+        ids: Option<&[&str]>,
+        search: Option<&str>,
+        tz: Option<Tz>,
+    ) -> impl Future<Output = Result<bool, Error>> + Send;
+
+    /// See [`MailpitClient::delete_messages`].
+    fn delete_messages(
+        &self,
+        message_ids: &[&str],
+    ) -> impl Future<Output = Result<bool, Error>> + Send;
+
+    /// See [`MailpitClient::get_search_messages`].
+    fn get_search_messages(
+        &self,
+        query: &str,
+        start: Option<usize>,
+        limit: Option<usize>,
+        tz: Option<Tz>,
+    ) -> impl Future<Output = Result<MessagesSummary, Error>> + Send;
+
+    /// See [`MailpitClient::delete_messages_by_search`].
+    fn delete_messages_by_search(
+        &self,
+        query: &str,
+        tz: Option<Tz>,
+    ) -> impl Future<Output = Result<bool, Error>> + Send;
+
+    #[cfg(feature = "checks")]
+    /// See [`MailpitClient::get_html_check`].
+    fn get_html_check(
+        &self,
+        id: &str,
+        locale: Option<&str>,
+    ) -> impl Future<Output = Result<HtmlCheckResponse, Error>> + Send;
+
+    #[cfg(feature = "checks")]
+    /// See [`MailpitClient::get_link_check`].
+    fn get_link_check(
+        &self,
+        id: &str,
+        follow: Option<bool>,
+    ) -> impl Future<Output = Result<LinkCheckResponse, Error>> + Send;
+
+    #[cfg(feature = "checks")]
+    /// See [`MailpitClient::get_spam_assassin_check`].
+    fn get_spam_assassin_check(
+        &self,
+        id: &str,
+    ) -> impl Future<Output = Result<SpamAssassinResponse, Error>> + Send;
+
+    #[cfg(feature = "tags")]
+    /// See [`MailpitClient::get_all_current_tags`].
+    fn get_all_current_tags(&self) -> impl Future<Output = Result<TagList, Error>> + Send;
+
+    #[cfg(feature = "tags")]
+    /// See [`MailpitClient::put_set_message_tags`].
+    fn put_set_message_tags(
+        &self,
+        ids: &[&str],
+        tags: &[&str],
+    ) -> impl Future<Output = Result<bool, Error>> + Send;
+
+    #[cfg(feature = "tags")]
+    /// See [`MailpitClient::put_rename_tag`].
+    fn put_rename_tag(
+        &self,
+        tag: &str,
+        name: &str,
+    ) -> impl Future<Output = Result<bool, Error>> + Send;
+
+    #[cfg(feature = "tags")]
+    /// See [`MailpitClient::delete_tag`].
+    fn delete_tag(&self, tag: &str) -> impl Future<Output = Result<bool, Error>> + Send;
+
+    #[cfg(feature = "chaos")]
+    /// See [`MailpitClient::get_chaos_triggers`].
+    fn get_chaos_triggers(
+        &self,
+    ) -> impl Future<Output = Result<ChaosTriggersResponse, Error>> + Send;
+
+    #[cfg(feature = "chaos")]
+    /// See [`MailpitClient::put_set_chaos_triggers`].
+    fn put_set_chaos_triggers(
+        &self,
+        config: Option<ChaosTriggersConfiguration>,
+    ) -> impl Future<Output = Result<ChaosTriggersResponse, Error>> + Send;
+
+    /// See [`MailpitClient::get_render_message_html_part`].
+    fn get_render_message_html_part(
+        &self,
+        id: &str,
+        embed: Option<EmbedMode>,
+    ) -> impl Future<Output = Result<String, Error>> + Send;
+
+    /// See [`MailpitClient::get_render_message_text_part`].
+    fn get_render_message_text_part(
+        &self,
+        id: &str,
+    ) -> impl Future<Output = Result<String, Error>> + Send;
+}
+
+impl MailpitApi for MailpitClient {
+    fn get_application_information(
+        &self,
+    ) -> impl Future<Output = Result<ApplicationInformation, Error>> + Send {
+        MailpitClient::get_application_information(self)
+    }
+
+    fn get_webui_configuration(
+        &self,
+    ) -> impl Future<Output = Result<WebUIConfiguration, Error>> + Send {
+        MailpitClient::get_webui_configuration(self)
+    }
+
+    fn get_message_summary(
+        &self,
+        id: &str,
+    ) -> impl Future<Output = Result<MessageSummary, Error>> + Send {
+        MailpitClient::get_message_summary(self, id)
+    }
+
+    fn get_message_headers(
+        &self,
+        id: &str,
+    ) -> impl Future<Output = Result<MessageHeaders, Error>> + Send {
+        MailpitClient::get_message_headers(self, id)
+    }
+
+    fn get_message_attachment(
+        &self,
+        id: &str,
+        part_id: &str,
+    ) -> impl Future<Output = Result<Bytes, Error>> + Send {
+        MailpitClient::get_message_attachment(self, id, part_id)
+    }
+
+    fn get_message_attachment_image_thumbnail(
+        &self,
+        id: &str,
+        part_id: &str,
+    ) -> impl Future<Output = Result<Bytes, Error>> + Send {
+        MailpitClient::get_message_attachment_image_thumbnail(self, id, part_id)
+    }
+
+    fn get_message_source(&self, id: &str) -> impl Future<Output = Result<String, Error>> + Send {
+        MailpitClient::get_message_source(self, id)
+    }
+
+    #[cfg(feature = "send")]
+    fn post_release_message(
+        &self,
+        id: &str,
+        to: &[&str],
+    ) -> impl Future<Output = Result<bool, Error>> + Send {
+        MailpitClient::post_release_message(self, id, to)
+    }
+
+    #[cfg(feature = "send")]
+    fn post_send_message(
+        &self,
+        message: SendMessage,
+    ) -> impl Future<Output = Result<SendMessageResponse, Error>> + Send {
+        MailpitClient::post_send_message(self, message)
+    }
+
+    fn get_list_messages(
+        &self,
+        start: Option<usize>,
+        limit: Option<usize>,
+    ) -> impl Future<Output = Result<MessagesSummary, Error>> + Send {
+        MailpitClient::get_list_messages(self, start, limit)
+    }
+
+    fn put_set_read_status(
+        &self,
+        read: Option<bool>,
+        ids: Option<&[&str]>,
+        search: Option<&str>,
+        tz: Option<Tz>,
+    ) -> impl Future<Output = Result<bool, Error>> + Send {
+        MailpitClient::put_set_read_status(self, read, ids, search, tz)
+    }
+
+    fn delete_messages(
+        &self,
+        message_ids: &[&str],
+    ) -> impl Future<Output = Result<bool, Error>> + Send {
+        MailpitClient::delete_messages(self, message_ids)
+    }
+
+    fn get_search_messages(
+        &self,
+        query: &str,
+        start: Option<usize>,
+        limit: Option<usize>,
+        tz: Option<Tz>,
+    ) -> impl Future<Output = Result<MessagesSummary, Error>> + Send {
+        MailpitClient::get_search_messages(self, query, start, limit, tz)
+    }
+
+    fn delete_messages_by_search(
+        &self,
+        query: &str,
+        tz: Option<Tz>,
+    ) -> impl Future<Output = Result<bool, Error>> + Send {
+        MailpitClient::delete_messages_by_search(self, query, tz)
+    }
+
+    #[cfg(feature = "checks")]
+    fn get_html_check(
+        &self,
+        id: &str,
+        locale: Option<&str>,
+    ) -> impl Future<Output = Result<HtmlCheckResponse, Error>> + Send {
+        MailpitClient::get_html_check(self, id, locale)
+    }
+
+    #[cfg(feature = "checks")]
+    fn get_link_check(
+        &self,
+        id: &str,
+        follow: Option<bool>,
+    ) -> impl Future<Output = Result<LinkCheckResponse, Error>> + Send {
+        MailpitClient::get_link_check(self, id, follow)
+    }
+
+    #[cfg(feature = "checks")]
+    fn get_spam_assassin_check(
+        &self,
+        id: &str,
+    ) -> impl Future<Output = Result<SpamAssassinResponse, Error>> + Send {
+        MailpitClient::get_spam_assassin_check(self, id)
+    }
+
+    #[cfg(feature = "tags")]
+    fn get_all_current_tags(&self) -> impl Future<Output = Result<TagList, Error>> + Send {
+        MailpitClient::get_all_current_tags(self)
+    }
+
+    #[cfg(feature = "tags")]
+    fn put_set_message_tags(
+        &self,
+        ids: &[&str],
+        tags: &[&str],
+    ) -> impl Future<Output = Result<bool, Error>> + Send {
+        MailpitClient::put_set_message_tags(self, ids, tags)
+    }
+
+    #[cfg(feature = "tags")]
+    fn put_rename_tag(
+        &self,
+        tag: &str,
+        name: &str,
+    ) -> impl Future<Output = Result<bool, Error>> + Send {
+        MailpitClient::put_rename_tag(self, tag, name)
+    }
+
+    #[cfg(feature = "tags")]
+    fn delete_tag(&self, tag: &str) -> impl Future<Output = Result<bool, Error>> + Send {
+        MailpitClient::delete_tag(self, tag)
+    }
+
+    #[cfg(feature = "chaos")]
+    fn get_chaos_triggers(
+        &self,
+    ) -> impl Future<Output = Result<ChaosTriggersResponse, Error>> + Send {
+        MailpitClient::get_chaos_triggers(self)
+    }
+
+    #[cfg(feature = "chaos")]
+    fn put_set_chaos_triggers(
+        &self,
+        config: Option<ChaosTriggersConfiguration>,
+    ) -> impl Future<Output = Result<ChaosTriggersResponse, Error>> + Send {
+        MailpitClient::put_set_chaos_triggers(self, config)
+    }
+
+    fn get_render_message_html_part(
+        &self,
+        id: &str,
+        embed: Option<EmbedMode>,
+    ) -> impl Future<Output = Result<String, Error>> + Send {
+        MailpitClient::get_render_message_html_part(self, id, embed)
+    }
+
+    fn get_render_message_text_part(
+        &self,
+        id: &str,
+    ) -> impl Future<Output = Result<String, Error>> + Send {
+        MailpitClient::get_render_message_text_part(self, id)
+    }
+}
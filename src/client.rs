@@ -1,50 +1,160 @@
+use std::{pin::pin, time::Duration};
+
 use base64::{Engine, prelude::BASE64_STANDARD};
 use bytes::Bytes;
+use chrono::{DateTime, Utc};
 use chrono_tz::Tz;
+use futures_util::{Stream, StreamExt, TryStreamExt, stream};
 use reqwest::{
-    Client, Url,
-    header::{self, HeaderMap, HeaderValue},
+    Client, StatusCode, Url,
+    header::{self, HeaderMap, HeaderName, HeaderValue},
 };
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+#[cfg(feature = "events")]
+use tokio_tungstenite::tungstenite::{Message, client::IntoClientRequest};
+use url::ParseError;
 
+#[cfg(feature = "events")]
+use crate::models::EventMessage;
+#[cfg(feature = "chaos")]
+use crate::models::{ChaosTriggersConfiguration, ChaosTriggersResponse};
+#[cfg(feature = "checks")]
+use crate::models::{HtmlCheckResponse, LinkCheckResponse, MessageReport, SpamAssassinResponse};
+#[cfg(feature = "send")]
+use crate::models::{
+    ReleaseMessageParams, ReleaseMessageResponse, SendMessage, SendMessageResponse,
+};
+#[cfg(feature = "tags")]
+use crate::models::{RenameTagParams, SetMessageTagsParams, TagList};
 use crate::{
     error::Error,
     models::{
-        ApplicationInformation, ChaosTriggersConfiguration, ChaosTriggersResponse,
-        DeleteMessagesFilter, HtmlCheckResponse, LinkCheckResponse, MessageHeaders, MessageSummary,
-        MessagesSummary, ReleaseMessageParams, RenameTagParams, SendMessage, SendMessageResponse,
-        SetMessageTagsParams, SetReadStatusParams, SpamAssassinResponse, TagList,
-        WebUIConfiguration,
+        ApplicationInformation, BulkMode, BulkOutcome, DeleteMessagesFilter, EmbedMode,
+        IngestSource, MailboxStats, MessageHeaders, MessageInfo, MessageSummary, MessagesSummary,
+        PollConfig, RenderedMessage, SetReadStatusParams, WebUIConfiguration,
     },
+    params::QueryParams,
+    search::{self, SearchQuery},
 };
 
+/// Page size used by helpers that page through all search results.
+const SEARCH_PAGE_SIZE: usize = 250;
+/// Number of times [`MailpitClient::drain`] tries to empty the mailbox.
+const DRAIN_ATTEMPTS: usize = 3;
+/// Number of concurrent requests sent by bulk helpers.
+const BULK_CONCURRENCY: usize = 8;
+
 pub struct MailpitClient {
     url: Url,
     client: Client,
+    preserve_read_status: bool,
+    default_page_limit: Option<usize>,
+    default_tz: Option<Tz>,
+    #[cfg(feature = "events")]
+    headers: HeaderMap,
+    #[cfg(feature = "send")]
+    default_send_tags: Vec<String>,
 }
 
 impl MailpitClient {
     /// Create a new [`MailpitClient`] for the given `url`.
+    ///
+    /// The path of `url` is kept, so Mailpit instances mounted under a
+    /// subpath like `https://example.com/mail/` are supported.
     pub fn new(url: &str) -> Result<Self, Error> {
-        let url = Url::parse(url)?;
-        Ok(Self {
-            url,
-            client: Client::new(),
-        })
+        Self::builder(url).build()
+    }
+
+    /// Create a new [`MailpitClient`] for the given `url`, failing requests
+    /// that take longer than `timeout` with [`Error::ReqwestFailure`].
+    ///
+    /// Clients created otherwise have no timeout, see
+    /// [`MailpitClientBuilder::timeout`].
+    pub fn new_with_timeout(url: &str, timeout: Duration) -> Result<Self, Error> {
+        Self::builder(url).timeout(timeout).build()
+    }
+
+    /// Create a new [`MailpitClient`] for the given `url` and make sure
+    /// the Mailpit instance is reachable by requesting its
+    /// [application information].
+    ///
+    /// Use [`MailpitClient::new`] to skip the round-trip.
+    ///
+    /// [application information]: crate::client::MailpitClient::get_application_information
+    pub async fn connect(url: &str) -> Result<Self, Error> {
+        let client = Self::new(url)?;
+        client.get_application_information().await?;
+        Ok(client)
+    }
+
+    /// Returns [`MailpitClientBuilder`] to create a [`MailpitClient`] for
+    /// the given `url`.
+    pub fn builder(url: &str) -> MailpitClientBuilder {
+        MailpitClientBuilder::new(url)
     }
 
     /// Create a new [`MailpitClient`] configured with Basic Authentication
     /// for the given `url`.
     pub fn new_with_auth(url: &str, username: &str, password: &str) -> Result<Self, Error> {
-        let url = Url::parse(url)?;
+        Self::builder(url).basic_auth(username, password).build()
+    }
 
-        let encoded = BASE64_STANDARD.encode(format!("{username}:{password}"));
-        let mut headers = HeaderMap::new();
-        let mut auth_value = HeaderValue::from_str(&format!("Basic {encoded}")).unwrap();
-        auth_value.set_sensitive(true);
-        headers.insert(header::AUTHORIZATION, auth_value);
+    /// Create a new [`MailpitClient`] for the given `url` sending its
+    /// requests with the given `client`, e.g. to share the connection pool
+    /// and TLS configuration of an application.
+    ///
+    /// All settings, including authentication, are taken from `client` as
+    /// it is.
+    #[cfg_attr(
+        feature = "events",
+        doc = "Default headers of `client` are not sent with the",
+        doc = "[`MailpitClient::subscribe_events`] upgrade request."
+    )]
+    pub fn with_client(url: &str, client: Client) -> Result<Self, Error> {
+        Ok(Self {
+            url: parse_base_url(url)?,
+            client,
+            preserve_read_status: false,
+            default_page_limit: None,
+            default_tz: None,
+            #[cfg(feature = "events")]
+            headers: HeaderMap::new(),
+            #[cfg(feature = "send")]
+            default_send_tags: Vec::new(),
+        })
+    }
+
+    /// Base URL of the Mailpit instance.
+    pub fn base_url(&self) -> &Url {
+        &self.url
+    }
+
+    /// URL of the given message in the Mailpit web UI, e.g. for printing
+    /// clickable links in test failure output.
+    ///
+    /// The ID can be set to `latest` to link the latest message.
+    pub fn web_ui_message_url(&self, id: &str) -> Url {
+        let mut url = self.url.clone();
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments.pop_if_empty().push("view").push(id);
+        }
+        url
+    }
+
+    /// Resolve the relative `path` against the base URL.
+    fn endpoint(&self, path: &str) -> Result<Url, Error> {
+        self.url.join(path).map_err(Into::into)
+    }
 
-        let client = Client::builder().default_headers(headers).build()?;
-        Ok(Self { url, client })
+    /// URL of the endpoint made of `segments`, which are percent-encoded,
+    /// so message IDs and part IDs can not change the path.
+    fn endpoint_segments(&self, segments: &[&str]) -> Url {
+        let mut url = self.url.clone();
+        if let Ok(mut path) = url.path_segments_mut() {
+            path.pop_if_empty().extend(segments);
+        }
+        url
     }
 
     /// #### Get application information
@@ -57,14 +167,10 @@ impl MailpitClient {
     pub async fn get_application_information(&self) -> Result<ApplicationInformation, Error> {
         let response = self
             .client
-            .get(format!("{}api/v1/info", self.url))
+            .get(self.endpoint("api/v1/info")?)
             .send()
             .await?;
-        Error::check_response(response)
-            .await?
-            .json()
-            .await
-            .map_err(Into::into)
+        Error::deserialize(Error::check_response(response).await?).await
     }
 
     /// #### Get web UI configuration
@@ -77,20 +183,170 @@ impl MailpitClient {
     pub async fn get_webui_configuration(&self) -> Result<WebUIConfiguration, Error> {
         let response = self
             .client
-            .get(format!("{}api/v1/webui", self.url))
+            .get(self.endpoint("api/v1/webui")?)
             .send()
             .await?;
-        Error::check_response(response)
-            .await?
-            .json()
-            .await
-            .map_err(Into::into)
+        Error::deserialize(Error::check_response(response).await?).await
+    }
+
+    #[cfg(feature = "events")]
+    /// #### Subscribe to events
+    /// __GET__ `/api/events`
+    ///
+    /// Opens the WebSocket Mailpit broadcasts its events on, like new or
+    /// deleted messages, and returns a stream of the received events, e.g.
+    /// to await the arrival of a message instead of polling. The stream
+    /// ends when Mailpit closes the connection.
+    ///
+    /// The stream owns the WebSocket and no background task is spawned for
+    /// it. Dropping the stream closes the connection right away, so no
+    /// explicit shutdown is needed, e.g. at the end of a test.
+    ///
+    /// Basic Authentication and the default headers configured with
+    /// [`MailpitClientBuilder`] are sent with the WebSocket upgrade
+    /// request.
+    ///
+    /// #### Errors:
+    /// - [`Error::WebSocket`] if the WebSocket can not be opened or fails
+    ///   while receiving events
+    pub async fn subscribe_events(
+        &self,
+    ) -> Result<impl Stream<Item = Result<EventMessage, Error>> + use<>, Error> {
+        let mut url = self.endpoint("api/events")?;
+        let scheme = if url.scheme() == "https" { "wss" } else { "ws" };
+        // Switching between special schemes always succeeds.
+        let _ = url.set_scheme(scheme);
+
+        let mut request = url.as_str().into_client_request()?;
+        request.headers_mut().extend(self.headers.clone());
+
+        let (socket, _) = tokio_tungstenite::connect_async(request).await?;
+        Ok(socket.filter_map(|message| async move {
+            match message {
+                Ok(Message::Text(text)) => Some(Error::deserialize_str(&text)),
+                Ok(_) => None,
+                Err(e) => Some(Err(e.into())),
+            }
+        }))
+    }
+
+    #[cfg(feature = "events")]
+    /// #### Watch the latest message
+    /// __GET__ `/api/events` and __GET__ `/api/v1/message/{ID}`
+    ///
+    /// Subscribes to the [events] and yields the summary of every newly
+    /// received message, e.g. for a live preview. Other events are
+    /// skipped. The stream ends when Mailpit closes the connection, dropping
+    /// it closes the WebSocket like with [events].
+    ///
+    /// Fetching a summary marks the message as read. With
+    /// [`MailpitClientBuilder::preserve_read_status`] enabled, messages
+    /// that arrived unread are marked as unread again right after, without
+    /// the extra lookup [`get_message_summary`] needs.
+    ///
+    /// #### Errors:
+    /// - [`Error::WebSocket`] if the WebSocket can not be opened or fails
+    ///   while receiving events
+    /// - __`404`__ - Not found error will return a 404 status code if a message is deleted before its summary is fetched
+    ///
+    /// [events]: crate::client::MailpitClient::subscribe_events
+    /// [`get_message_summary`]: crate::client::MailpitClient::get_message_summary
+    pub async fn watch_latest(
+        &self,
+    ) -> Result<impl Stream<Item = Result<MessageSummary, Error>> + '_, Error> {
+        let events = self.subscribe_events().await?;
+        Ok(events
+            .try_filter_map(|event| async move {
+                match event {
+                    EventMessage::NewMessage(message) => Ok(Some(message)),
+                    _ => Ok(None),
+                }
+            })
+            .and_then(move |message| async move {
+                let summary = self.fetch_message_summary(message.id()).await?;
+                if self.preserve_read_status && !message.read {
+                    self.restore_unread(Some(message.id())).await?;
+                }
+                Ok(summary)
+            }))
+    }
+
+    /// #### Mailbox statistics
+    /// __GET__ `/api/v1/info` and __GET__ `/api/v1/messages`
+    ///
+    /// Returns the message counts of the mailbox together with the tag
+    /// counts, database size and runtime information of the Mailpit
+    /// instance, fetched concurrently.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    pub async fn stats(&self) -> Result<MailboxStats, Error> {
+        let (info, messages) = futures_util::try_join!(
+            self.get_application_information(),
+            self.get_list_messages(None, Some(0))
+        )?;
+
+        Ok(MailboxStats {
+            total: messages.total,
+            unread: messages.unread,
+            tags: info.tags,
+            database_size: info.database_size,
+            memory: info.runtime_stats.memory,
+            uptime: Duration::from_secs(info.runtime_stats.uptime as u64),
+            version: info.version,
+        })
+    }
+
+    /// #### Get instance label
+    /// __GET__ `/api/v1/webui`
+    ///
+    /// Returns the label of the Mailpit instance, e.g. to make sure tests
+    /// talk to the right instance in multi-instance setups. The label can
+    /// only be set when starting Mailpit with `--label`, there is no API
+    /// to change it.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    pub async fn get_instance_label(&self) -> Result<String, Error> {
+        Ok(self.get_webui_configuration().await?.label)
+    }
+
+    /// #### Duplicates ignored
+    /// __GET__ `/api/v1/webui`
+    ///
+    /// Returns whether Mailpit was started with `--ignore-duplicate-ids`,
+    /// dropping messages with a `Message-ID` that is already stored.
+    ///
+    /// To confirm that a specific duplicate was dropped, compare the
+    /// [`RuntimeStats::smtp_ignored`] counter before and after sending it,
+    /// e.g. with [`RuntimeStats::delta`]:
+    ///
+    /// ```no_run
+    /// # async fn example(client: mailpit_client::MailpitClient) -> Result<(), mailpit_client::error::Error> {
+    /// assert!(client.duplicates_ignored().await?);
+    /// let before = client.get_application_information().await?.runtime_stats;
+    /// // send the duplicate via SMTP
+    /// let after = client.get_application_information().await?.runtime_stats;
+    /// assert_eq!(Some(1), after.delta(&before).smtp_ignored);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    ///
+    /// [`RuntimeStats::smtp_ignored`]: crate::models::RuntimeStats::smtp_ignored
+    /// [`RuntimeStats::delta`]: crate::models::RuntimeStats::delta
+    pub async fn duplicates_ignored(&self) -> Result<bool, Error> {
+        Ok(self.get_webui_configuration().await?.duplicates_ignored)
     }
 
     /// #### Get message summary
     /// __GET__ `/api/v1/message/{ID}`
     ///
-    /// Returns the summary of a message, marking the message as read.
+    /// Returns the summary of a message, marking the message as read. See
+    /// [`MailpitClientBuilder::preserve_read_status`] to keep unread
+    /// messages unread.
     ///
     /// The ID can be set to `latest` to return the latest message.
     ///
@@ -98,16 +354,110 @@ impl MailpitClient {
     /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
     /// - __`404`__ - Not found error will return a 404 status code
     pub async fn get_message_summary(&self, id: &str) -> Result<MessageSummary, Error> {
+        let unread = self.unread_before_fetch(id).await?;
+        let summary = self
+            .fetch_message_summary(unread.as_deref().unwrap_or(id))
+            .await?;
+        self.restore_unread(unread.as_deref()).await?;
+        Ok(summary)
+    }
+
+    /// Fetch the summary of message `id`, which marks it as read regardless
+    /// of [`MailpitClientBuilder::preserve_read_status`].
+    async fn fetch_message_summary(&self, id: &str) -> Result<MessageSummary, Error> {
         let response = self
             .client
-            .get(format!("{}api/v1/message/{id}", self.url))
+            .get(self.endpoint_segments(&["api", "v1", "message", id]))
             .send()
             .await?;
-        Error::check_response(response)
-            .await?
-            .json()
-            .await
-            .map_err(Into::into)
+        Error::deserialize(Error::check_response(response).await?).await
+    }
+
+    /// #### Get latest message summary
+    /// __GET__ `/api/v1/message/latest`
+    ///
+    /// Returns the summary of the latest message, marking the message as read.
+    /// Shorthand for [`get_message_summary`] with the ID `latest`.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    /// - __`404`__ - Not found error will return a 404 status code
+    ///
+    /// [`get_message_summary`]: crate::client::MailpitClient::get_message_summary
+    pub async fn get_latest_message(&self) -> Result<MessageSummary, Error> {
+        self.get_message_summary("latest").await
+    }
+
+    /// #### Get multiple message summaries
+    /// __GET__ `/api/v1/message/{ID}`
+    ///
+    /// Returns the summaries of all messages with the given `ids`, marking
+    /// the messages as read. See [`BulkMode`] for how failing messages are
+    /// handled.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    /// - __`404`__ - Not found error will return a 404 status code
+    pub async fn get_messages(
+        &self,
+        ids: &[&str],
+        mode: BulkMode,
+    ) -> Result<BulkOutcome<MessageSummary>, Error> {
+        bulk(ids, mode, |id| self.get_message_summary(id)).await
+    }
+
+    /// #### Get message summary as raw JSON
+    /// __GET__ `/api/v1/message/{ID}`
+    ///
+    /// Returns the summary of a message as untyped JSON, marking the
+    /// message as read. Intended for debugging payloads that fail to
+    /// deserialize into a [`MessageSummary`].
+    ///
+    /// The ID can be set to `latest` to return the latest message.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    /// - __`404`__ - Not found error will return a 404 status code
+    pub async fn get_message_raw_json(&self, id: &str) -> Result<serde_json::Value, Error> {
+        let unread = self.unread_before_fetch(id).await?;
+        let response = self
+            .client
+            .get(self.endpoint_segments(&["api", "v1", "message", unread.as_deref().unwrap_or(id)]))
+            .send()
+            .await?;
+        let value: serde_json::Value =
+            Error::deserialize(Error::check_response(response).await?).await?;
+        self.restore_unread(unread.as_deref()).await?;
+        Ok(value)
+    }
+
+    /// Database ID of message `id` if it is unread, looked up with
+    /// [`get_message_light`] before fetching the message marks it as read.
+    /// Always `None` unless [`MailpitClientBuilder::preserve_read_status`]
+    /// is enabled.
+    ///
+    /// The returned ID should be fetched instead of `id`, so `latest` can
+    /// not resolve to a newer message in between.
+    ///
+    /// [`get_message_light`]: crate::client::MailpitClient::get_message_light
+    async fn unread_before_fetch(&self, id: &str) -> Result<Option<String>, Error> {
+        if !self.preserve_read_status {
+            return Ok(None);
+        }
+
+        let message = self.get_message_light(id).await?;
+        Ok(message
+            .filter(|message| !message.read)
+            .map(|message| message.id().to_string()))
+    }
+
+    /// Mark the message `unread` as unread again after fetching it, if set.
+    async fn restore_unread(&self, unread: Option<&str>) -> Result<(), Error> {
+        if let Some(id) = unread {
+            self.put_set_read_status(Some(false), Some(&[id]), None, None)
+                .await?;
+        }
+        Ok(())
     }
 
     /// #### Get message headers
@@ -123,14 +473,127 @@ impl MailpitClient {
     pub async fn get_message_headers(&self, id: &str) -> Result<MessageHeaders, Error> {
         let response = self
             .client
-            .get(format!("{}api/v1/message/{id}/headers", self.url))
+            .get(self.endpoint_segments(&["api", "v1", "message", id, "headers"]))
             .send()
             .await?;
-        Error::check_response(response)
-            .await?
-            .json()
-            .await
-            .map_err(Into::into)
+        Error::deserialize(Error::check_response(response).await?).await
+    }
+
+    /// #### Get latest message headers
+    /// __GET__ `/api/v1/message/latest/headers`
+    ///
+    /// Returns the headers of the latest message. Shorthand for
+    /// [`get_message_headers`] with the ID `latest`.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    /// - __`404`__ - Not found error will return a 404 status code
+    ///
+    /// [`get_message_headers`]: crate::client::MailpitClient::get_message_headers
+    pub async fn get_latest_message_headers(&self) -> Result<MessageHeaders, Error> {
+        self.get_message_headers("latest").await
+    }
+
+    /// #### Get message header
+    /// __GET__ `/api/v1/message/{ID}/headers`
+    ///
+    /// Returns the values of a single message header. The header `name`
+    /// is matched case-insensitively, `None` is returned if the message
+    /// has no such header. This is a conveniency wrapper around
+    /// [`get_message_headers`].
+    ///
+    /// The ID can be set to `latest` to return the latest message.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    /// - __`404`__ - Not found error will return a 404 status code
+    ///
+    /// [`get_message_headers`]: crate::client::MailpitClient::get_message_headers
+    pub async fn get_message_header(
+        &self,
+        id: &str,
+        name: &str,
+    ) -> Result<Option<Vec<String>>, Error> {
+        let headers = self.get_message_headers(id).await?;
+        Ok(headers
+            .into_iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, values)| values))
+    }
+
+    /// #### Get message ingest source
+    /// __GET__ `/api/v1/message/{ID}/headers`
+    ///
+    /// Returns whether the message was received via SMTP or the Send API.
+    /// Mailpit does not expose this directly, see [`IngestSource`] for the
+    /// heuristic used.
+    ///
+    /// The ID can be set to `latest` to return the latest message.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    /// - __`404`__ - Not found error will return a 404 status code
+    pub async fn get_message_ingest_source(&self, id: &str) -> Result<IngestSource, Error> {
+        let headers = self.get_message_headers(id).await?;
+        Ok(IngestSource::from_headers(&headers))
+    }
+
+    /// #### Get ordered message headers
+    /// __GET__ `/api/v1/message/{ID}/raw`
+    ///
+    /// Returns the message headers as `(name, value)` pairs in on-the-wire
+    /// order, including duplicates like multiple `Received` headers.
+    /// Folded header values are unfolded.
+    ///
+    /// Unlike [`get_message_headers`], which returns the headers grouped
+    /// by name in alphabetical order, this parses the raw message source,
+    /// so the whole message is downloaded.
+    ///
+    /// The ID can be set to `latest` to return the latest message.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    /// - __`404`__ - Not found error will return a 404 status code
+    ///
+    /// [`get_message_headers`]: crate::client::MailpitClient::get_message_headers
+    pub async fn get_message_headers_ordered(
+        &self,
+        id: &str,
+    ) -> Result<Vec<(String, String)>, Error> {
+        let source = self.get_message_source(id).await?;
+        Ok(parse_headers(&source))
+    }
+
+    /// #### Get message part headers
+    /// __GET__ `/api/v1/message/{ID}/raw`
+    ///
+    /// Returns the MIME headers of a single message part, e.g. to check the
+    /// `Content-Disposition` of an attachment exactly as sent. Mailpit does
+    /// not expose part headers, so they are parsed from the raw message.
+    /// Nested part IDs are separated by dots like `1.2`, matching the
+    /// part IDs used by Mailpit.
+    ///
+    /// The ID can be set to `latest` to return the latest message.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    /// - __`404`__ - Not found error will return a 404 status code
+    /// - [`Error::PartNotFound`] if the message has no part with the given `part_id`
+    pub async fn get_message_part_headers(
+        &self,
+        id: &str,
+        part_id: &str,
+    ) -> Result<MessageHeaders, Error> {
+        let source = self.get_message_source(id).await?;
+        let part = find_part(&source, part_id).ok_or_else(|| Error::PartNotFound {
+            part_id: part_id.to_string(),
+        })?;
+
+        let mut headers = MessageHeaders::new();
+        for (name, value) in parse_headers(part) {
+            headers.entry(name).or_default().push(value);
+        }
+        Ok(headers)
     }
 
     /// #### Get message attachment
@@ -146,7 +609,7 @@ impl MailpitClient {
     pub async fn get_message_attachment(&self, id: &str, part_id: &str) -> Result<Bytes, Error> {
         let response = self
             .client
-            .get(format!("{}api/v1/message/{id}/part/{part_id}", self.url))
+            .get(self.endpoint_segments(&["api", "v1", "message", id, "part", part_id]))
             .send()
             .await?;
         Error::check_response(response)
@@ -156,146 +619,675 @@ impl MailpitClient {
             .map_err(Into::into)
     }
 
-    /// #### Get an attachment image thumbnail
-    /// __GET__ `/api/v1/message/{ID}/part/{PartID}/thumb`
+    /// #### Get message attachment SHA-256
+    /// __GET__ `/api/v1/message/{ID}/part/{PartID}`
     ///
-    /// This will return a cropped 180x120 JPEG thumbnail of an image
-    /// attachment. If the image is smaller than 180x120 then the image
-    /// is padded. If the attachment is not an image then a blank image
-    /// is returned.
+    /// Returns the lowercase hex encoded SHA-256 digest of the attachment
+    /// part, e.g. for deduplication or golden-file tests. The part is hashed
+    /// while downloading, so memory usage stays flat for large parts.
     ///
-    /// The ID can be set to `latest` to return the latest message.
+    /// The ID can be set to `latest` to use the latest message.
     ///
     /// #### Errors:
     /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
-    pub async fn get_message_attachment_image_thumbnail(
+    /// - __`404`__ - Not found error will return a 404 status code
+    pub async fn get_message_attachment_sha256(
         &self,
         id: &str,
         part_id: &str,
-    ) -> Result<Bytes, Error> {
+    ) -> Result<String, Error> {
         let response = self
             .client
-            .get(format!(
-                "{}api/v1/message/{id}/part/{part_id}/thumb",
-                self.url
-            ))
+            .get(self.endpoint_segments(&["api", "v1", "message", id, "part", part_id]))
             .send()
             .await?;
-        Error::check_response(response)
-            .await?
-            .bytes()
-            .await
-            .map_err(Into::into)
+        let mut response = Error::check_response(response).await?;
+
+        let mut hasher = Sha256::new();
+        while let Some(chunk) = response.chunk().await? {
+            hasher.update(&chunk);
+        }
+
+        Ok(hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect())
     }
 
-    /// #### Get message source
-    /// __GET__ `/api/v1/message/{ID}/raw`
+    /// #### Get message attachment range
+    /// __GET__ `/api/v1/message/{ID}/part/{PartID}`
     ///
-    /// Returns the full email source as plain text.
+    /// Returns the bytes `start..=end` of the attachment part by sending a
+    /// `Range` header, e.g. to resume an interrupted download. Without an
+    /// `end` all bytes from `start` to the end of the part are returned.
+    ///
+    /// A server ignoring the `Range` header responds with `200` and the
+    /// whole part instead of `206 Partial Content`. Both are returned as is,
+    /// so callers must check the length of the returned bytes.
     ///
     /// The ID can be set to `latest` to return the latest message.
     ///
     /// #### Errors:
     /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
     /// - __`404`__ - Not found error will return a 404 status code
-    pub async fn get_message_source(&self, id: &str) -> Result<String, Error> {
+    /// - __`416`__ - Range not satisfiable error will return a 416 status code if `start` is past the end of the part
+    pub async fn get_message_attachment_range(
+        &self,
+        id: &str,
+        part_id: &str,
+        start: u64,
+        end: Option<u64>,
+    ) -> Result<Bytes, Error> {
+        let range = match end {
+            Some(end) => format!("bytes={start}-{end}"),
+            None => format!("bytes={start}-"),
+        };
         let response = self
             .client
-            .get(format!("{}api/v1/message/{id}/raw", self.url))
+            .get(self.endpoint_segments(&["api", "v1", "message", id, "part", part_id]))
+            .header(header::RANGE, range)
             .send()
             .await?;
         Error::check_response(response)
             .await?
-            .text()
+            .bytes()
             .await
             .map_err(Into::into)
     }
 
-    /// #### Release message
-    /// __POST__ `/api/v1/message/{ID}/release`
+    /// #### Get message attachment size
+    /// __GET__ `/api/v1/message/{ID}` and `/api/v1/message/{ID}/part/{PartID}`
     ///
-    /// Release a message via a pre-configured external SMTP server.
-    /// This is only enabled if message relaying has been configured.
+    /// Returns the size in bytes of an attachment part without downloading
+    /// it, e.g. to pre-allocate a buffer. The size is looked up in this order:
+    /// 1. The attachments and inline attachments of the [message summary].
+    ///    As with [`get_message_summary`], this marks the message as read
+    ///    unless [`MailpitClientBuilder::preserve_read_status`] is enabled.
+    /// 2. The first byte of the part, requested with a `Range` header, for
+    ///    parts not listed in the summary. The size is read from its
+    ///    `Content-Range` header, or from `Content-Length` if the range is
+    ///    ignored.
     ///
-    /// The ID can be set to `latest` to return the latest message.
+    /// Returns `None` if the size is unknown to both of them, or if the
+    /// server does not allow requesting the part.
     ///
-    /// `To` is a list of addresses.
+    /// The ID can be set to `latest` to use the latest message.
     ///
     /// #### Errors:
     /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
     /// - __`404`__ - Not found error will return a 404 status code
-    pub async fn post_release_message(&self, id: &str, to: &[&str]) -> Result<bool, Error> {
+    ///
+    /// [message summary]: crate::client::MailpitClient::get_message_summary
+    /// [`get_message_summary`]: crate::client::MailpitClient::get_message_summary
+    pub async fn get_message_attachment_size(
+        &self,
+        id: &str,
+        part_id: &str,
+    ) -> Result<Option<usize>, Error> {
+        let summary = self.get_message_summary(id).await?;
+        if let Some(attachment) = summary.find_attachment(part_id) {
+            return Ok(Some(attachment.size));
+        }
+
         let response = self
             .client
-            .post(format!("{}api/v1/message/{id}/release", self.url))
-            .json(&ReleaseMessageParams { to })
+            .get(self.endpoint_segments(&["api", "v1", "message", summary.id(), "part", part_id]))
+            .header(header::RANGE, "bytes=0-0")
             .send()
             .await?;
-        Error::check_response(response)
-            .await?
-            .text()
-            .await
-            .map(|t| t == "ok")
-            .map_err(Into::into)
+        if response.status() == StatusCode::METHOD_NOT_ALLOWED {
+            return Ok(None);
+        }
+        let response = Error::check_response(response).await?;
+        let value = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+        };
+        let size = if response.status() == StatusCode::PARTIAL_CONTENT {
+            value(header::CONTENT_RANGE)
+                .and_then(|range| range.rsplit_once('/'))
+                .and_then(|(_, total)| total.parse().ok())
+        } else {
+            value(header::CONTENT_LENGTH).and_then(|length| length.parse().ok())
+        };
+        Ok(size)
     }
 
-    /// #### Send a message
-    /// __POST__ `/api/v1/send`
+    /// #### Get all message attachments as ZIP
+    /// __GET__ `/api/v1/message/{ID}` and `/api/v1/message/{ID}/part/{PartID}`
     ///
-    /// Release a message via a pre-configured external SMTP server.
-    /// This is only enabled if message relaying has been configured.
+    /// Downloads all attachments and inline attachments of a message and
+    /// bundles them into a ZIP archive, using each part's filename.
+    /// Duplicate filenames are de-duplicated with a numeric suffix, e.g.
+    /// `report (1).pdf`. As this fetches the message summary, the message
+    /// is marked as read.
     ///
-    /// The ID can be set to `latest` to return the latest message.
+    /// Mailpit has no endpoint for this, so the archive is built
+    /// client-side. Requires the `zip` feature.
     ///
-    /// `To` is a list of addresses.
+    /// The ID can be set to `latest` to return the latest message.
     ///
     /// #### Errors:
-    /// - __`400`__ - Server error will return with a 400 status code with a JSON error response in the body
-    pub async fn post_send_message(
-        &self,
-        message: SendMessage,
-    ) -> Result<SendMessageResponse, Error> {
-        let response = self
-            .client
-            .post(format!("{}api/v1/send", self.url))
-            .json(&message)
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    /// - __`404`__ - Not found error will return a 404 status code
+    #[cfg(feature = "zip")]
+    pub async fn get_message_attachments_zip(&self, id: &str) -> Result<Bytes, Error> {
+        use std::{
+            collections::HashSet,
+            io::{Cursor, Write},
+        };
+        use zip::{ZipWriter, write::SimpleFileOptions};
+
+        let message = self.get_message_summary(id).await?;
+        // Resolve `latest` only once, so all parts belong to the same message.
+        let id = message.id();
+
+        let mut names = HashSet::new();
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        for part in message.attachments().iter().chain(&message.inline) {
+            let content = self.get_message_attachment(id, &part.part_id).await?;
+
+            let name = if part.file_name.is_empty() {
+                format!("part-{}", part.part_id)
+            } else {
+                part.file_name.clone()
+            };
+            let name = unique_file_name(&name, &mut names);
+
+            zip.start_file(name, SimpleFileOptions::default())?;
+            zip.write_all(&content)?;
+        }
+
+        Ok(zip.finish()?.into_inner().into())
+    }
+
+    /// #### Get an attachment image thumbnail
+    /// __GET__ `/api/v1/message/{ID}/part/{PartID}/thumb`
+    ///
+    /// This will return a cropped 180x120 JPEG thumbnail of an image
+    /// attachment. If the image is smaller than 180x120 then the image
+    /// is padded. If the attachment is not an image then a blank image
+    /// is returned.
+    ///
+    /// The ID can be set to `latest` to return the latest message.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    pub async fn get_message_attachment_image_thumbnail(
+        &self,
+        id: &str,
+        part_id: &str,
+    ) -> Result<Bytes, Error> {
+        let response = self
+            .client
+            .get(self.endpoint_segments(&["api", "v1", "message", id, "part", part_id, "thumb"]))
+            .send()
+            .await?;
+        Error::check_response(response)
+            .await?
+            .bytes()
+            .await
+            .map_err(Into::into)
+    }
+
+    /// #### Get message source
+    /// __GET__ `/api/v1/message/{ID}/raw`
+    ///
+    /// Returns the full email source as plain text.
+    ///
+    /// The ID can be set to `latest` to return the latest message.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    /// - __`404`__ - Not found error will return a 404 status code
+    pub async fn get_message_source(&self, id: &str) -> Result<String, Error> {
+        let response = self
+            .client
+            .get(self.endpoint_segments(&["api", "v1", "message", id, "raw"]))
             .send()
             .await?;
         Error::check_response(response)
             .await?
-            .json()
+            .text()
             .await
             .map_err(Into::into)
     }
 
+    /// #### Get message source and write it
+    /// __GET__ `/api/v1/message/{ID}/raw`
+    ///
+    /// Returns the raw email source while also writing it to `writer`
+    /// as it is downloaded, e.g. to keep a copy of a message in a file
+    /// while parsing it, without downloading it twice. `writer` is flushed
+    /// before returning.
+    ///
+    /// The ID can be set to `latest` to return the latest message.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    /// - __`404`__ - Not found error will return a 404 status code
+    pub async fn get_message_eml_tee(
+        &self,
+        id: &str,
+        writer: impl AsyncWrite,
+    ) -> Result<Bytes, Error> {
+        let response = self
+            .client
+            .get(self.endpoint_segments(&["api", "v1", "message", id, "raw"]))
+            .send()
+            .await?;
+        let mut response = Error::check_response(response).await?;
+
+        let mut writer = pin!(writer);
+        let mut source = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            writer.write_all(&chunk).await?;
+            source.extend_from_slice(&chunk);
+        }
+        writer.flush().await?;
+
+        Ok(source.into())
+    }
+
+    /// #### Get latest message source
+    /// __GET__ `/api/v1/message/latest/raw`
+    ///
+    /// Returns the full email source of the latest message as plain text.
+    /// Shorthand for [`get_message_source`] with the ID `latest`.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    /// - __`404`__ - Not found error will return a 404 status code
+    ///
+    /// [`get_message_source`]: crate::client::MailpitClient::get_message_source
+    pub async fn get_latest_message_source(&self) -> Result<String, Error> {
+        self.get_message_source("latest").await
+    }
+
+    #[cfg(feature = "send")]
+    /// #### Release message
+    /// __POST__ `/api/v1/message/{ID}/release`
+    ///
+    /// Release a message via a pre-configured external SMTP server.
+    /// This is only enabled if message relaying has been configured.
+    ///
+    /// The ID can be set to `latest` to return the latest message.
+    ///
+    /// `To` is a list of addresses.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body,
+    ///   [`Error::RelayNotConfigured`] if message relaying is disabled
+    /// - __`404`__ - Not found error will return a 404 status code
+    pub async fn post_release_message(&self, id: &str, to: &[&str]) -> Result<bool, Error> {
+        let response = self
+            .client
+            .post(self.endpoint_segments(&["api", "v1", "message", id, "release"]))
+            .json(&ReleaseMessageParams { to })
+            .send()
+            .await?;
+        let response = Error::check_response(response)
+            .await
+            .map_err(Error::relay_not_configured)?;
+        Error::expect_ok(response).await
+    }
+
+    #[cfg(feature = "send")]
+    /// #### Release message with details
+    /// __POST__ `/api/v1/message/{ID}/release`
+    ///
+    /// Like [`post_release_message`], but returns the details of the
+    /// release instead of a bare `bool`.
+    ///
+    /// Current Mailpit versions respond with a plain `ok`, which is
+    /// returned as `message`. A JSON body with `Message` and `To` fields is
+    /// parsed as is. If the server does not echo the recipients, `to` holds
+    /// the requested recipients.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    /// - [`Error::RelayNotConfigured`] if message relaying is not configured
+    ///
+    /// [`post_release_message`]: crate::client::MailpitClient::post_release_message
+    pub async fn post_release_message_verbose(
+        &self,
+        id: &str,
+        to: &[&str],
+    ) -> Result<ReleaseMessageResponse, Error> {
+        let response = self
+            .client
+            .post(self.endpoint_segments(&["api", "v1", "message", id, "release"]))
+            .json(&ReleaseMessageParams { to })
+            .send()
+            .await?;
+        let response = Error::check_response(response)
+            .await
+            .map_err(Error::relay_not_configured)?;
+
+        let body = response.text().await?;
+        let mut release = if body.trim_start().starts_with('{') {
+            serde_json::from_str(&body)?
+        } else {
+            ReleaseMessageResponse {
+                message: body.trim().to_string(),
+                to: Vec::new(),
+            }
+        };
+        if release.to.is_empty() {
+            release.to = to.iter().map(|to| to.to_string()).collect();
+        }
+
+        Ok(release)
+    }
+
+    #[cfg(feature = "send")]
+    /// #### Send a message
+    /// __POST__ `/api/v1/send`
+    ///
+    /// Release a message via a pre-configured external SMTP server.
+    /// This is only enabled if message relaying has been configured.
+    ///
+    /// The ID can be set to `latest` to return the latest message.
+    ///
+    /// `To` is a list of addresses.
+    ///
+    /// Tags set with [`MailpitClientBuilder::default_send_tag`] are added
+    /// to the tags of `message`.
+    ///
+    /// The Send API has no field for the `Username` of a message. Like for
+    /// SMTP, Mailpit records the authenticated user instead, so use
+    /// [`MailpitClient::new_with_auth`] if Mailpit requires authentication
+    /// for the Send API. Without authentication the username stays empty.
+    ///
+    /// The message is always sent uncompressed, Mailpit does not accept a
+    /// `Content-Encoding` on requests.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with a JSON error response in the body
+    pub async fn post_send_message(
+        &self,
+        mut message: SendMessage,
+    ) -> Result<SendMessageResponse, Error> {
+        for tag in &self.default_send_tags {
+            if !message.tags.contains(tag) {
+                message.tags.push(tag.clone());
+            }
+        }
+
+        let response = self
+            .client
+            .post(self.endpoint("api/v1/send")?)
+            .json(&message)
+            .send()
+            .await?;
+        Error::deserialize(Error::check_response(response).await?).await
+    }
+
+    #[cfg(feature = "send")]
+    /// #### Send many messages
+    /// __POST__ `/api/v1/send`
+    ///
+    /// Sends all `messages` with up to `concurrency` requests in flight,
+    /// e.g. to seed a mailbox in a test fixture without overwhelming
+    /// Mailpit. The responses are returned in the order of `messages`.
+    ///
+    /// The first failure aborts sending the remaining messages and is
+    /// returned as [`Error::SendManyFailed`] containing the index of the
+    /// failed message. Messages already sent are not deleted.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with a JSON error response in the body
+    pub async fn send_many(
+        &self,
+        messages: Vec<SendMessage>,
+        concurrency: usize,
+    ) -> Result<Vec<SendMessageResponse>, Error> {
+        let mut responses: Vec<_> = messages.iter().map(|_| None).collect();
+        let mut results = stream::iter(messages.into_iter().enumerate())
+            .map(|(index, message)| async move { (index, self.post_send_message(message).await) })
+            .buffer_unordered(concurrency.max(1));
+
+        while let Some((index, result)) = results.next().await {
+            match result {
+                Ok(response) => responses[index] = Some(response),
+                Err(e) => {
+                    return Err(Error::SendManyFailed {
+                        index,
+                        source: Box::new(e),
+                    });
+                }
+            }
+        }
+
+        Ok(responses.into_iter().flatten().collect())
+    }
+
     /// ####  List messages
     /// __GET__ `/api/v1/messages`
     ///
     /// Returns messages from the mailbox ordered from newest to oldest.
     ///
+    /// A `limit` of `Some(0)` returns only the message counts without any
+    /// messages, whereas `None` uses the
+    /// [`MailpitClientBuilder::default_page_limit`] if set, else the
+    /// server's default limit.
+    ///
+    /// Timestamps are always returned in UTC. Mailpit ignores a `tz` on this
+    /// endpoint, it only uses one to interpret the dates of a search, see
+    /// [`get_search_messages`].
+    ///
     /// #### Errors:
     /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    ///
+    /// [`get_search_messages`]: crate::client::MailpitClient::get_search_messages
     pub async fn get_list_messages(
         &self,
         start: Option<usize>,
         limit: Option<usize>,
     ) -> Result<MessagesSummary, Error> {
-        let mut builder = self.client.get(format!("{}api/v1/messages", self.url));
+        let builder = QueryParams::new()
+            .opt("start", start)
+            .opt("limit", limit.or(self.default_page_limit))
+            .apply(self.client.get(self.endpoint("api/v1/messages")?));
+
+        let response = builder.send().await?;
+        Error::deserialize_large(Error::check_response(response).await?).await
+    }
+
+    /// #### List messages chronologically
+    /// __GET__ `/api/v1/messages`
+    ///
+    /// Same as [`get_list_messages`], but with the messages of the page
+    /// sorted by received date (ascending), oldest first. The counts are
+    /// kept as is.
+    ///
+    /// Only the returned page is reversed: `start` still counts from the
+    /// newest message, so the first page holds the newest messages. To
+    /// walk the whole mailbox oldest first, collect all pages, e.g. via
+    /// [`search_messages_stream`], and sort them with
+    /// [`MessageInfo::sort_by_date`].
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    ///
+    /// [`get_list_messages`]: crate::client::MailpitClient::get_list_messages
+    /// [`search_messages_stream`]: crate::client::MailpitClient::search_messages_stream
+    pub async fn get_list_messages_chronological(
+        &self,
+        start: Option<usize>,
+        limit: Option<usize>,
+    ) -> Result<MessagesSummary, Error> {
+        let mut summary = self.get_list_messages(start, limit).await?;
+        summary.messages.reverse();
+        Ok(summary)
+    }
 
-        if let Some(v) = start {
-            builder = builder.query(&[("start", v)]);
+    /// #### Get messages by ID
+    /// __GET__ `/api/v1/message/{ID}/headers` and __GET__ `/api/v1/search`
+    ///
+    /// Returns the list entries of the messages with the given `ids`, in the
+    /// order of `ids`, without marking them as read. IDs that do not exist
+    /// are skipped and duplicate IDs are returned once.
+    ///
+    /// Mailpit has no search filter for database IDs, so the messages are
+    /// looked up concurrently with [`get_message_light`].
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    ///
+    /// [`get_message_light`]: crate::client::MailpitClient::get_message_light
+    pub async fn get_messages_by_ids(&self, ids: &[&str]) -> Result<Vec<MessageInfo>, Error> {
+        let mut unique = Vec::with_capacity(ids.len());
+        for id in ids {
+            if !unique.contains(id) {
+                unique.push(*id);
+            }
         }
 
-        if let Some(v) = limit {
-            builder = builder.query(&[("limit", v)]);
+        let messages: Vec<_> = stream::iter(unique)
+            .map(|id| self.get_message_light(id))
+            .buffered(BULK_CONCURRENCY)
+            .try_collect()
+            .await?;
+        Ok(messages.into_iter().flatten().collect())
+    }
+
+    /// #### Get lightweight message
+    /// __GET__ `/api/v1/message/{ID}/headers` and __GET__ `/api/v1/search`
+    ///
+    /// Returns the list entry of message `id`, or `None` if it does not
+    /// exist, without marking it as read. Mailpit cannot limit the fields
+    /// of a message summary or search for database IDs, so this searches
+    /// for the `Message-ID` header of the message and picks the entry with
+    /// the matching ID, which needs two requests. Only messages without a
+    /// searchable `Message-ID` header fall back to paging through the
+    /// message list.
+    ///
+    /// A [`MessageInfo`] provides the addresses, subject, tags, size,
+    /// number of attachments, read status, receive time and a snippet of
+    /// the body. The bodies, headers, attachment details and the `Date`
+    /// header are only part of the [`MessageSummary`], see
+    /// [`get_message_summary`].
+    ///
+    /// The ID can be set to `latest` to return the latest message, which
+    /// needs a single request.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    ///
+    /// [`get_message_summary`]: crate::client::MailpitClient::get_message_summary
+    pub async fn get_message_light(&self, id: &str) -> Result<Option<MessageInfo>, Error> {
+        if id == "latest" {
+            let latest = self.get_list_messages(None, Some(1)).await?;
+            return Ok(latest.messages.into_iter().next());
         }
 
-        let response = builder.send().await?;
-        Error::check_response(response)
-            .await?
-            .json()
-            .await
-            .map_err(Into::into)
+        let headers = match self.get_message_headers(id).await {
+            Ok(headers) => headers,
+            Err(Error::HttpFailure { status: 404, .. }) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let search = headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("Message-ID"))
+            .and_then(|(_, values)| values.first())
+            .and_then(|message_id| message_id_search(message_id).ok());
+
+        let Some(search) = search else {
+            let mut messages = pin!(paginate(SEARCH_PAGE_SIZE, |start, limit| {
+                self.get_list_messages(Some(start), Some(limit))
+            }));
+            while let Some(message) = messages.try_next().await? {
+                if message.id() == id {
+                    return Ok(Some(message));
+                }
+            }
+            return Ok(None);
+        };
+
+        let found = self
+            .get_search_messages(&search, None, Some(SEARCH_PAGE_SIZE), None)
+            .await?;
+        Ok(found
+            .messages
+            .into_iter()
+            .find(|message| message.id() == id))
+    }
+
+    /// #### Export messages as NDJSON
+    /// __GET__ `/api/v1/messages` and __GET__ `/api/v1/message/{ID}`
+    ///
+    /// Writes the summary of every message in the mailbox to `writer` as
+    /// newline-delimited JSON, one [`MessageSummary`] per line, newest
+    /// first. Returns the number of messages written. Every line can be
+    /// deserialized into a [`MessageSummary`] again.
+    ///
+    /// Messages are listed page by page and `writer` is flushed after every
+    /// page, so memory usage stays bounded regardless of the mailbox size.
+    /// Fetching a summary marks the message as read, so messages listed as
+    /// unread are marked as unread again right after, regardless of
+    /// [`MailpitClientBuilder::preserve_read_status`].
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    /// - __`404`__ - Not found error will return a 404 status code if a message is deleted during the export
+    pub async fn export_messages_ndjson(&self, writer: impl AsyncWrite) -> Result<usize, Error> {
+        let mut writer = pin!(writer);
+        let mut summaries = pin!(
+            paginate(SEARCH_PAGE_SIZE, |start, limit| {
+                self.get_list_messages(Some(start), Some(limit))
+            })
+            .map_ok(|message| async move {
+                let summary = self.fetch_message_summary(message.id()).await?;
+                if !message.read {
+                    self.restore_unread(Some(message.id())).await?;
+                }
+                Ok(summary)
+            })
+            .try_buffered(BULK_CONCURRENCY)
+        );
+
+        let mut count = 0;
+        while let Some(summary) = summaries.try_next().await? {
+            let mut line = serde_json::to_vec(&summary)?;
+            line.push(b'\n');
+            writer.write_all(&line).await?;
+
+            count += 1;
+            if count % SEARCH_PAGE_SIZE == 0 {
+                writer.flush().await?;
+            }
+        }
+        writer.flush().await?;
+
+        Ok(count)
+    }
+
+    /// #### Count messages
+    /// __GET__ `/api/v1/messages`
+    ///
+    /// Returns the total number of messages in the mailbox, without
+    /// fetching any messages.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    pub async fn count_messages(&self) -> Result<usize, Error> {
+        Ok(self.get_list_messages(None, Some(0)).await?.total)
+    }
+
+    /// #### Count unread messages
+    /// __GET__ `/api/v1/messages`
+    ///
+    /// Returns the number of unread messages in the mailbox, without
+    /// fetching any messages.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    pub async fn count_unread(&self) -> Result<usize, Error> {
+        Ok(self.get_list_messages(None, Some(0)).await?.unread)
     }
 
     /// #### Set read status
@@ -303,7 +1295,8 @@ impl MailpitClient {
     ///
     /// You can optionally provide an array of IDs or a search string.
     /// If neither IDs nor search is provided then all mailbox messages
-    /// are updated.
+    /// are updated. Providing both returns [`Error::InvalidRequest`]
+    /// without sending a request, as Mailpit only applies one of them.
     ///
     /// `To` is a list of addresses.
     ///
@@ -316,12 +1309,16 @@ impl MailpitClient {
         search: Option<&str>,
         tz: Option<Tz>,
     ) -> Result<bool, Error> {
-        let mut builder = self.client.put(format!("{}api/v1/messages", self.url));
-
-        if let Some(tz) = tz {
-            builder = builder.query(&[("tz", tz)]);
+        if ids.is_some() && search.is_some() {
+            return Err(Error::InvalidRequest(
+                "set either `ids` or `search` to update the read status, not both".to_string(),
+            ));
         }
 
+        let builder = QueryParams::new()
+            .tz(tz.or(self.default_tz))
+            .apply(self.client.put(self.endpoint("api/v1/messages")?));
+
         let response = builder
             .json(&SetReadStatusParams {
                 ids,
@@ -330,12 +1327,7 @@ impl MailpitClient {
             })
             .send()
             .await?;
-        Error::check_response(response)
-            .await?
-            .text()
-            .await
-            .map(|t| t == "ok")
-            .map_err(Into::into)
+        Error::expect_ok(Error::check_response(response).await?).await
     }
 
     /// #### Delete all messages
@@ -352,6 +1344,130 @@ impl MailpitClient {
         self.delete_messages(&[]).await
     }
 
+    /// #### Drain the mailbox
+    /// __DELETE__ `/api/v1/messages` and __DELETE__ `/api/v1/tags/{Tag}`
+    ///
+    /// Deletes all messages and tags, returning once the mailbox reports
+    /// zero messages. Messages arriving while draining are deleted as
+    /// well, giving up with [`Error::DrainIncomplete`] if the mailbox is
+    /// still not empty after a few attempts. Safe to call on an already
+    /// empty mailbox. Tags are only deleted with the `tags` feature enabled.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    pub async fn drain(&self) -> Result<(), Error> {
+        let mut remaining = 0;
+        for _ in 0..DRAIN_ATTEMPTS {
+            self.delete_all_messages().await?;
+            #[cfg(feature = "tags")]
+            for tag in self.get_all_current_tags().await? {
+                self.delete_tag(&tag).await?;
+            }
+
+            remaining = self.count_messages().await?;
+            if remaining == 0 {
+                return Ok(());
+            }
+        }
+
+        Err(Error::DrainIncomplete { remaining })
+    }
+
+    /// #### Wait until the mailbox is empty
+    /// __GET__ `/api/v1/messages`
+    ///
+    /// Polls the number of messages as configured by `poll` until it is
+    /// zero, e.g. to make sure deletes have been processed before the next
+    /// test starts. Returns [`Error::Timeout`] if the mailbox is still not
+    /// empty after the timeout.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    pub async fn wait_until_empty(&self, poll: PollConfig) -> Result<(), Error> {
+        let deadline = tokio::time::Instant::now() + poll.timeout;
+        loop {
+            if self.count_messages().await? == 0 {
+                return Ok(());
+            }
+
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                return Err(Error::Timeout(poll.timeout));
+            }
+            tokio::time::sleep(poll.next_delay().min(deadline - now)).await;
+        }
+    }
+
+    /// #### Wait until Mailpit is ready
+    /// __GET__ `/readyz`
+    ///
+    /// Polls the readiness endpoint as configured by `poll` until Mailpit
+    /// reports to be ready, e.g. while the Mailpit container of a test
+    /// setup is still booting. Network errors like a refused connection and
+    /// server errors are retried, any other error is returned right away.
+    /// Returns [`Error::Timeout`] if Mailpit is not ready after the
+    /// timeout.
+    ///
+    /// #### Errors:
+    /// - __`4xx`__ - Client errors, e.g. due to missing authentication, are not retried
+    pub async fn wait_ready(&self, poll: PollConfig) -> Result<(), Error> {
+        let deadline = tokio::time::Instant::now() + poll.timeout;
+        loop {
+            let result = match self.client.get(self.endpoint("readyz")?).send().await {
+                Ok(response) => Error::check_response(response).await.map(drop),
+                Err(e) => Err(e.into()),
+            };
+            match result {
+                Ok(()) => return Ok(()),
+                Err(Error::ReqwestFailure(_)) => {}
+                Err(e) if e.status_code().is_some_and(|s| s.is_server_error()) => {}
+                Err(e) => return Err(e),
+            }
+
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                return Err(Error::Timeout(poll.timeout));
+            }
+            tokio::time::sleep(poll.next_delay().min(deadline - now)).await;
+        }
+    }
+
+    /// #### Wait for read status
+    /// __GET__ `/api/v1/message/{ID}/headers` and __GET__ `/api/v1/search`
+    ///
+    /// Polls the read status of message `id` as configured by `poll` until
+    /// it equals `read`, e.g. to wait for a consumer that marks messages as
+    /// read asynchronously. The status is looked up with
+    /// [`get_message_light`], so polling does not mark the message as
+    /// read. A message that does not exist (yet) is polled like a message
+    /// with a different status. Returns [`Error::Timeout`] if the status
+    /// does not match after the timeout.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    ///
+    /// [`get_message_light`]: crate::client::MailpitClient::get_message_light
+    pub async fn wait_for_read_status(
+        &self,
+        id: &str,
+        read: bool,
+        poll: PollConfig,
+    ) -> Result<(), Error> {
+        let deadline = tokio::time::Instant::now() + poll.timeout;
+        loop {
+            let message = self.get_message_light(id).await?;
+            if message.is_some_and(|message| message.read == read) {
+                return Ok(());
+            }
+
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                return Err(Error::Timeout(poll.timeout));
+            }
+            tokio::time::sleep(poll.next_delay().min(deadline - now)).await;
+        }
+    }
+
     /// #### Delete messages
     /// __DELETE__ `/api/v1/messages`
     ///
@@ -363,16 +1479,11 @@ impl MailpitClient {
     pub async fn delete_messages(&self, message_ids: &[&str]) -> Result<bool, Error> {
         let response = self
             .client
-            .delete(format!("{}api/v1/messages", self.url))
+            .delete(self.endpoint("api/v1/messages")?)
             .json(&DeleteMessagesFilter { ids: message_ids })
             .send()
             .await?;
-        Error::check_response(response)
-            .await?
-            .text()
-            .await
-            .map(|t| t == "ok")
-            .map_err(Into::into)
+        Error::expect_ok(Error::check_response(response).await?).await
     }
 
     /// #### Search messages
@@ -381,6 +1492,15 @@ impl MailpitClient {
     /// Returns messages matching a search, sorted by received date
     /// (descending).
     ///
+    /// A `limit` of `Some(0)` returns only the message counts without any
+    /// messages, whereas `None` uses the
+    /// [`MailpitClientBuilder::default_page_limit`] if set, else the
+    /// server's default limit.
+    ///
+    /// `tz` is the time zone used to interpret `before:` and `after:` dates
+    /// of the search, timestamps in the response are always UTC. If `None`,
+    /// the [`MailpitClientBuilder::default_tz`] is used if set.
+    ///
     /// `To` is a list of addresses.
     ///
     /// #### Errors:
@@ -392,84 +1512,348 @@ impl MailpitClient {
         limit: Option<usize>,
         tz: Option<Tz>,
     ) -> Result<MessagesSummary, Error> {
-        let mut builder = self
-            .client
-            .get(format!("{}api/v1/search", self.url))
-            .query(&[("query", query)]);
+        let builder = QueryParams::new()
+            .param("query", query)
+            .opt("start", start)
+            .opt("limit", limit.or(self.default_page_limit))
+            .tz(tz.or(self.default_tz))
+            .apply(self.client.get(self.endpoint("api/v1/search")?));
 
-        if let Some(start) = start {
-            builder = builder.query(&[("start", start)]);
-        }
+        let response = builder.send().await?;
+        Error::deserialize(Error::check_response(response).await?).await
+    }
+
+    /// #### Search messages by sender
+    /// __GET__ `/api/v1/search`
+    ///
+    /// Returns messages sent by `email`, sorted by received date
+    /// (descending). Shorthand for [`get_search_messages`] with a
+    /// `from:` search, quoting `email` if needed.
+    ///
+    /// #### Errors:
+    /// - [`Error::InvalidSearchQuery`] if `email` contains a double quote
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    ///
+    /// [`get_search_messages`]: crate::client::MailpitClient::get_search_messages
+    pub async fn get_messages_from(
+        &self,
+        email: &str,
+        start: Option<usize>,
+        limit: Option<usize>,
+        tz: Option<Tz>,
+    ) -> Result<MessagesSummary, Error> {
+        let query = SearchQuery::new().from(email).try_build()?;
+        self.get_search_messages(&query, start, limit, tz).await
+    }
+
+    /// #### Search messages by subject
+    /// __GET__ `/api/v1/search`
+    ///
+    /// Returns messages with the given `subject`, sorted by received date
+    /// (descending). Shorthand for [`get_search_messages`] with a
+    /// `subject:` search, quoting `subject` if needed.
+    ///
+    /// #### Errors:
+    /// - [`Error::InvalidSearchQuery`] if `subject` contains a double quote
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    ///
+    /// [`get_search_messages`]: crate::client::MailpitClient::get_search_messages
+    pub async fn get_messages_with_subject(
+        &self,
+        subject: &str,
+        start: Option<usize>,
+        limit: Option<usize>,
+        tz: Option<Tz>,
+    ) -> Result<MessagesSummary, Error> {
+        let query = SearchQuery::new().subject(subject).try_build()?;
+        self.get_search_messages(&query, start, limit, tz).await
+    }
+
+    /// #### Unread messages
+    /// __GET__ `/api/v1/search`
+    ///
+    /// Returns the unread messages, sorted by received date (descending).
+    /// Shorthand for [`get_search_messages`] with an `is:unread` search,
+    /// see [`count_unread`] for just the number of unread messages.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    ///
+    /// [`get_search_messages`]: crate::client::MailpitClient::get_search_messages
+    /// [`count_unread`]: crate::client::MailpitClient::count_unread
+    pub async fn get_unread_messages(
+        &self,
+        start: Option<usize>,
+        limit: Option<usize>,
+    ) -> Result<MessagesSummary, Error> {
+        let query = SearchQuery::new().is_read(false).build();
+        self.get_search_messages(&query, start, limit, None).await
+    }
+
+    /// #### Stream search results
+    /// __GET__ `/api/v1/search`
+    ///
+    /// Returns a stream over all messages matching a search, sorted by
+    /// received date (descending). Results are fetched lazily in pages of
+    /// `page_size` messages until all matches are consumed, so memory
+    /// usage stays bounded regardless of the number of matches.
+    ///
+    /// Pages are requested by offset, so messages arriving or being
+    /// deleted while the stream is consumed may shift results between
+    /// pages.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    pub fn search_messages_stream<'a>(
+        &'a self,
+        query: &'a str,
+        page_size: usize,
+        tz: Option<Tz>,
+    ) -> impl Stream<Item = Result<MessageInfo, Error>> + 'a {
+        paginate(page_size, move |start, limit| {
+            self.get_search_messages(query, Some(start), Some(limit), tz)
+        })
+    }
 
-        if let Some(limit) = limit {
-            builder = builder.query(&[("limit", limit)]);
+    /// #### Expect a single message
+    /// __GET__ `/api/v1/search` and __GET__ `/api/v1/message/{ID}`
+    ///
+    /// Returns the summary of the only message matching [a search](https://mailpit.axllent.org/docs/usage/search-filters/),
+    /// marking the message as read. Fails with [`Error::MatchCount`] if no
+    /// or more than one message matches, e.g. to assert that exactly
+    /// one message was sent to a recipient.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    /// - __`404`__ - Not found error will return a 404 status code
+    pub async fn expect_message(&self, query: &str) -> Result<MessageSummary, Error> {
+        let found = self.get_search_messages(query, None, Some(2), None).await?;
+        match found.messages.as_slice() {
+            [message] if found.messages_count == 1 => self.get_message_summary(message.id()).await,
+            _ => Err(Error::MatchCount {
+                query: query.to_string(),
+                count: found.messages_count,
+            }),
         }
+    }
 
-        if let Some(tz) = tz {
-            builder = builder.query(&[("tz", tz)]);
+    /// #### Get message by Message-ID
+    /// __GET__ `/api/v1/search` and __GET__ `/api/v1/message/{ID}`
+    ///
+    /// Returns the summary of the message with the given `Message-ID`
+    /// header, marking the message as read, or `None` if there is no such
+    /// message. Surrounding angle brackets of `message_id` are optional.
+    /// Fails with [`Error::MatchCount`] if multiple messages share
+    /// the `Message-ID`, e.g. when the same message was delivered twice.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    /// - __`404`__ - Not found error will return a 404 status code
+    pub async fn get_message_by_message_id(
+        &self,
+        message_id: &str,
+    ) -> Result<Option<MessageSummary>, Error> {
+        let query = message_id_search(message_id)?;
+        let found = self
+            .get_search_messages(&query, None, Some(2), None)
+            .await?;
+        match found.messages.as_slice() {
+            [] => Ok(None),
+            [message] if found.messages_count == 1 => {
+                self.get_message_summary(message.id()).await.map(Some)
+            }
+            _ => Err(Error::MatchCount {
+                query,
+                count: found.messages_count,
+            }),
         }
+    }
+
+    /// #### Delete messages by search
+    /// __DELETE__ `/api/v1/search`
+    ///
+    /// Delete all messages matching [a search](https://mailpit.axllent.org/docs/usage/search-filters/).
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    pub async fn delete_messages_by_search(
+        &self,
+        query: &str,
+        tz: Option<Tz>,
+    ) -> Result<bool, Error> {
+        let builder = QueryParams::new()
+            .param("query", query)
+            .tz(tz.or(self.default_tz))
+            .apply(self.client.delete(self.endpoint("api/v1/search")?));
 
         let response = builder.send().await?;
-        Error::check_response(response)
-            .await?
-            .json()
-            .await
-            .map_err(Into::into)
+        Error::expect_ok(Error::check_response(response).await?).await
+    }
+
+    /// #### Delete messages by multiple searches
+    /// __DELETE__ `/api/v1/search`
+    ///
+    /// Delete all messages matching any of the given
+    /// [searches](https://mailpit.axllent.org/docs/usage/search-filters/),
+    /// returning the successfully executed queries. See [`BulkMode`] for how
+    /// failing queries are handled.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    pub async fn delete_messages_by_searches(
+        &self,
+        queries: &[&str],
+        tz: Option<Tz>,
+        mode: BulkMode,
+    ) -> Result<BulkOutcome<String>, Error> {
+        bulk(queries, mode, |query| async move {
+            self.delete_messages_by_search(query, tz).await?;
+            Ok(query.to_string())
+        })
+        .await
+    }
+
+    /// #### Delete messages by search, collecting their IDs
+    /// __GET__ `/api/v1/search` and __DELETE__ `/api/v1/messages`
+    ///
+    /// Searches for all messages matching [a search](https://mailpit.axllent.org/docs/usage/search-filters/),
+    /// deletes them by ID and returns the IDs of the deleted messages.
+    ///
+    /// Messages that arrive between the search and the delete request
+    /// are neither deleted nor included in the returned IDs.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    pub async fn delete_messages_by_search_collecting(
+        &self,
+        query: &str,
+        tz: Option<Tz>,
+    ) -> Result<Vec<String>, Error> {
+        let ids: Vec<String> = self
+            .search_messages_stream(query, SEARCH_PAGE_SIZE, tz)
+            .map_ok(|message| message.base.id)
+            .try_collect()
+            .await?;
+
+        // An empty list of IDs would delete all messages.
+        if !ids.is_empty() {
+            let id_refs: Vec<&str> = ids.iter().map(String::as_str).collect();
+            self.delete_messages(&id_refs).await?;
+        }
+
+        Ok(ids)
+    }
+
+    /// #### Delete messages older than
+    /// __GET__ `/api/v1/search` and __DELETE__ `/api/v1/messages`
+    ///
+    /// Deletes all messages received more than `age` ago, e.g. to enforce
+    /// a retention policy, and returns the number of deleted messages.
+    /// Shorthand for [`delete_messages_before`] with the current time minus
+    /// `age`.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    ///
+    /// [`delete_messages_before`]: crate::client::MailpitClient::delete_messages_before
+    pub async fn delete_messages_older_than(
+        &self,
+        age: Duration,
+        tz: Option<Tz>,
+    ) -> Result<usize, Error> {
+        self.delete_messages_before(search::cutoff(age), tz).await
+    }
+
+    /// #### Delete messages before
+    /// __GET__ `/api/v1/search` and __DELETE__ `/api/v1/messages`
+    ///
+    /// Deletes all messages received before `cutoff` and returns the number
+    /// of deleted messages.
+    ///
+    /// The cutoff is sent as RFC 3339 timestamp in UTC, or with the offset
+    /// of `tz` if set, so both denote the same instant. See
+    /// [`delete_messages_by_search_collecting`] for messages arriving while
+    /// deleting.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    ///
+    /// [`delete_messages_by_search_collecting`]: crate::client::MailpitClient::delete_messages_by_search_collecting
+    pub async fn delete_messages_before(
+        &self,
+        cutoff: DateTime<Utc>,
+        tz: Option<Tz>,
+    ) -> Result<usize, Error> {
+        let query = match tz {
+            Some(tz) => SearchQuery::new().before(cutoff.with_timezone(&tz)),
+            None => SearchQuery::new().before(cutoff),
+        };
+
+        let deleted = self
+            .delete_messages_by_search_collecting(&query.build(), tz)
+            .await?;
+        Ok(deleted.len())
     }
 
-    /// #### Delete messages by search
-    /// __DELETE__ `/api/v1/search`
+    #[cfg(feature = "checks")]
+    /// #### HTML check
+    /// __GET__ `/api/v1/message/{ID}/html-check`
     ///
-    /// Delete all messages matching [a search](https://mailpit.axllent.org/docs/usage/search-filters/).
+    /// Returns the summary of the message HTML checker.
+    ///
+    /// The ID can be set to `latest` to return the latest message.
+    ///
+    /// `locale` is sent as `Accept-Language` header to request localized
+    /// warnings. Mailpit currently ignores it and always responds in
+    /// English, so `None` (the server default) is usually what you want.
     ///
     /// #### Errors:
     /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
-    pub async fn delete_messages_by_search(
+    /// - __`404`__ - Not found error will return a 404 status code
+    pub async fn get_html_check(
         &self,
-        query: &str,
-        tz: Option<Tz>,
-    ) -> Result<bool, Error> {
-        let mut builder = self
-            .client
-            .delete(format!("{}api/v1/search", self.url))
-            .query(&[("query", query)]);
+        id: &str,
+        locale: Option<&str>,
+    ) -> Result<HtmlCheckResponse, Error> {
+        let mut builder =
+            self.client
+                .get(self.endpoint_segments(&["api", "v1", "message", id, "html-check"]));
 
-        if let Some(tz) = tz {
-            builder = builder.query(&[("tz", tz)]);
+        if let Some(locale) = locale {
+            builder = builder.header(header::ACCEPT_LANGUAGE, locale);
         }
 
         let response = builder.send().await?;
-        Error::check_response(response)
-            .await?
-            .text()
-            .await
-            .map(|t| t == "ok")
-            .map_err(Into::into)
+        Error::deserialize_large(Error::check_response(response).await?).await
     }
 
-    /// #### HTML check
+    #[cfg(feature = "checks")]
+    /// #### HTML check, if enabled
     /// __GET__ `/api/v1/message/{ID}/html-check`
     ///
-    /// Returns the summary of the message HTML checker.
+    /// Same as [`get_html_check`], but returns `None` if the HTML check is
+    /// disabled on the Mailpit server.
     ///
     /// The ID can be set to `latest` to return the latest message.
     ///
     /// #### Errors:
     /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
     /// - __`404`__ - Not found error will return a 404 status code
-    pub async fn get_html_check(&self, id: &str) -> Result<HtmlCheckResponse, Error> {
-        let response = self
-            .client
-            .get(format!("{}api/v1/message/{id}/html-check", self.url))
-            .send()
-            .await?;
-        Error::check_response(response)
-            .await?
-            .json()
-            .await
-            .map_err(Into::into)
+    ///
+    /// [`get_html_check`]: crate::client::MailpitClient::get_html_check
+    pub async fn get_html_check_opt(
+        &self,
+        id: &str,
+        locale: Option<&str>,
+    ) -> Result<Option<HtmlCheckResponse>, Error> {
+        match self.get_html_check(id, locale).await {
+            Ok(check) => Ok(Some(check)),
+            Err(e) if e.is_disabled("html check") => Ok(None),
+            Err(e) => Err(e),
+        }
     }
 
+    #[cfg(feature = "checks")]
     /// #### Link check
     /// __GET__ `/api/v1/message/{ID}/link-check`
     ///
@@ -485,22 +1869,16 @@ impl MailpitClient {
         id: &str,
         follow: Option<bool>,
     ) -> Result<LinkCheckResponse, Error> {
-        let mut builder = self
-            .client
-            .get(format!("{}api/v1/message/{id}/link-check", self.url));
-
-        if let Some(follow) = follow {
-            builder = builder.query(&[("follow", follow.to_string())])
-        }
+        let builder = QueryParams::new().opt("follow", follow).apply(
+            self.client
+                .get(self.endpoint_segments(&["api", "v1", "message", id, "link-check"])),
+        );
 
         let response = builder.send().await?;
-        Error::check_response(response)
-            .await?
-            .json()
-            .await
-            .map_err(Into::into)
+        Error::deserialize(Error::check_response(response).await?).await
     }
 
+    #[cfg(feature = "checks")]
     /// #### SpamAssassin check
     /// __GET__ `/api/v1/message/{ID}/sa-check`
     ///
@@ -514,16 +1892,90 @@ impl MailpitClient {
     pub async fn get_spam_assassin_check(&self, id: &str) -> Result<SpamAssassinResponse, Error> {
         let response = self
             .client
-            .get(format!("{}api/v1/message/{id}/sa-check", self.url))
+            .get(self.endpoint_segments(&["api", "v1", "message", id, "sa-check"]))
             .send()
             .await?;
-        Error::check_response(response)
-            .await?
-            .json()
-            .await
-            .map_err(Into::into)
+        Error::deserialize(Error::check_response(response).await?).await
+    }
+
+    #[cfg(feature = "checks")]
+    /// #### SpamAssassin check, if enabled
+    /// __GET__ `/api/v1/message/{ID}/sa-check`
+    ///
+    /// Same as [`get_spam_assassin_check`], but returns `None` if
+    /// SpamAssassin is disabled on the Mailpit server.
+    ///
+    /// The ID can be set to `latest` to return the latest message.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    /// - __`404`__ - Not found error will return a 404 status code
+    ///
+    /// [`get_spam_assassin_check`]: crate::client::MailpitClient::get_spam_assassin_check
+    pub async fn get_spam_assassin_check_opt(
+        &self,
+        id: &str,
+    ) -> Result<Option<SpamAssassinResponse>, Error> {
+        match self.get_spam_assassin_check(id).await {
+            Ok(check) => Ok(Some(check)),
+            Err(e) if e.is_disabled("spamassassin") => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    #[cfg(feature = "checks")]
+    /// #### Get message report
+    /// __GET__ `/api/v1/message/{ID}` and its HTML, link and SpamAssassin checks
+    ///
+    /// Returns the summary of a message together with the results of all
+    /// checks, fetched concurrently. Like [`get_message_summary`] this marks
+    /// the message as read, unless
+    /// [`MailpitClientBuilder::preserve_read_status`] is enabled.
+    ///
+    /// Checks disabled at runtime, e.g. SpamAssassin, are `None`. Any other
+    /// error, like an unknown ID or a failing check, fails the whole report.
+    ///
+    /// The ID can be set to `latest` to return the latest message. Its
+    /// summary is fetched first, so the checks are run on the same message
+    /// even if another one arrives in between.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    /// - __`404`__ - Not found error will return a 404 status code
+    ///
+    /// [`get_message_summary`]: crate::client::MailpitClient::get_message_summary
+    pub async fn get_message_report(&self, id: &str) -> Result<MessageReport, Error> {
+        // Resolve `latest` first, so the summary and all checks belong to the
+        // same message.
+        let (latest, id) = if id == "latest" {
+            let summary = self.get_message_summary(id).await?;
+            let id = summary.id().to_string();
+            (Some(summary), id)
+        } else {
+            (None, id.to_string())
+        };
+
+        let (summary, html_check, link_check, spam_assassin_check) = futures_util::join!(
+            async {
+                match latest {
+                    Some(summary) => Ok(summary),
+                    None => self.get_message_summary(&id).await,
+                }
+            },
+            self.get_html_check(&id, None),
+            self.get_link_check(&id, None),
+            self.get_spam_assassin_check(&id)
+        );
+
+        Ok(MessageReport {
+            summary: summary?,
+            html_check: optional_check(html_check, "html check")?,
+            link_check: optional_check(link_check, "link check")?,
+            spam_assassin_check: optional_check(spam_assassin_check, "spamassassin")?,
+        })
     }
 
+    #[cfg(feature = "tags")]
     /// #### Get all current tags
     /// __GET__ `/api/v1/tags`
     ///
@@ -534,16 +1986,42 @@ impl MailpitClient {
     pub async fn get_all_current_tags(&self) -> Result<TagList, Error> {
         let response = self
             .client
-            .get(format!("{}api/v1/tags", self.url))
+            .get(self.endpoint("api/v1/tags")?)
             .send()
             .await?;
-        Error::check_response(response)
-            .await?
-            .json()
-            .await
-            .map_err(Into::into)
+        Error::deserialize(Error::check_response(response).await?).await
+    }
+
+    #[cfg(feature = "tags")]
+    /// #### Get all current tags with message counts
+    /// __GET__ `/api/v1/tags` and `/api/v1/info`
+    ///
+    /// Returns all unique message tags together with the number of messages
+    /// tagged with them, sorted by count in descending order. Tags with the
+    /// same count are sorted by name.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    pub async fn get_tags_with_counts(&self) -> Result<Vec<(String, usize)>, Error> {
+        let (tags, info) = futures_util::try_join!(
+            self.get_all_current_tags(),
+            self.get_application_information()
+        )?;
+
+        let mut tags: Vec<_> = tags
+            .into_iter()
+            .map(|tag| {
+                let count = info.tags.get(&tag).copied().unwrap_or_default();
+                (tag, count)
+            })
+            .collect();
+        tags.sort_by(|(a_tag, a_count), (b_tag, b_count)| {
+            b_count.cmp(a_count).then_with(|| a_tag.cmp(b_tag))
+        });
+        Ok(tags)
     }
 
+    #[cfg(feature = "tags")]
     /// #### Set message tags
     /// __PUT__ `/api/v1/tags`
     ///
@@ -556,18 +2034,14 @@ impl MailpitClient {
     pub async fn put_set_message_tags(&self, ids: &[&str], tags: &[&str]) -> Result<bool, Error> {
         let response = self
             .client
-            .put(format!("{}api/v1/tags", self.url))
+            .put(self.endpoint("api/v1/tags")?)
             .json(&SetMessageTagsParams { ids, tags })
             .send()
             .await?;
-        Error::check_response(response)
-            .await?
-            .text()
-            .await
-            .map(|t| t == "ok")
-            .map_err(Into::into)
+        Error::expect_ok(Error::check_response(response).await?).await
     }
 
+    #[cfg(feature = "tags")]
     /// #### Rename a tag
     /// __PUT__ `/api/v1/tags/{Tag}`
     ///
@@ -579,18 +2053,14 @@ impl MailpitClient {
         let tag = urlencoding::encode(tag);
         let response = self
             .client
-            .put(format!("{}api/v1/tags/{tag}", self.url))
+            .put(self.endpoint(&format!("api/v1/tags/{tag}"))?)
             .json(&RenameTagParams { name })
             .send()
             .await?;
-        Error::check_response(response)
-            .await?
-            .text()
-            .await
-            .map(|t| t == "ok")
-            .map_err(Into::into)
+        Error::expect_ok(Error::check_response(response).await?).await
     }
 
+    #[cfg(feature = "tags")]
     /// #### Delete a tag
     /// __DELETE__ `/api/v1/tags/{Tag}`
     ///
@@ -603,17 +2073,13 @@ impl MailpitClient {
         let tag = urlencoding::encode(tag);
         let response = self
             .client
-            .delete(format!("{}api/v1/tags/{tag}", self.url))
+            .delete(self.endpoint(&format!("api/v1/tags/{tag}"))?)
             .send()
             .await?;
-        Error::check_response(response)
-            .await?
-            .text()
-            .await
-            .map(|t| t == "ok")
-            .map_err(Into::into)
+        Error::expect_ok(Error::check_response(response).await?).await
     }
 
+    #[cfg(feature = "chaos")]
     /// #### Get Chaos triggers
     /// __Get__ `/api/v1/chaos`
     ///
@@ -625,16 +2091,45 @@ impl MailpitClient {
     pub async fn get_chaos_triggers(&self) -> Result<ChaosTriggersResponse, Error> {
         let response = self
             .client
-            .get(format!("{}api/v1/chaos", self.url))
+            .get(self.endpoint("api/v1/chaos")?)
             .send()
             .await?;
-        Error::check_response(response)
-            .await?
-            .json()
-            .await
-            .map_err(Into::into)
+        Error::deserialize(Error::check_response(response).await?).await
+    }
+
+    #[cfg(feature = "chaos")]
+    /// #### Get Chaos triggers, if enabled
+    /// __Get__ `/api/v1/chaos`
+    ///
+    /// Same as [`get_chaos_triggers`], but returns `None` if Chaos is not
+    /// enabled at runtime.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    ///
+    /// [`get_chaos_triggers`]: crate::client::MailpitClient::get_chaos_triggers
+    pub async fn get_chaos_triggers_opt(&self) -> Result<Option<ChaosTriggersResponse>, Error> {
+        match self.get_chaos_triggers().await {
+            Ok(triggers) => Ok(Some(triggers)),
+            Err(e) if e.is_disabled("chaos") => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    #[cfg(feature = "chaos")]
+    /// #### Chaos enabled
+    /// __GET__ `/api/v1/webui`
+    ///
+    /// Returns whether Chaos is enabled at runtime, e.g. to skip Chaos
+    /// tests against instances started without `--enable-chaos`.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    pub async fn chaos_enabled(&self) -> Result<bool, Error> {
+        Ok(self.get_webui_configuration().await?.chaos_enabled)
     }
 
+    #[cfg(feature = "chaos")]
     /// #### Set Chaos triggers
     /// __PUT__ `/api/v1/chaos`
     ///
@@ -648,22 +2143,51 @@ impl MailpitClient {
     /// default values.
     ///
     /// #### Errors:
+    /// - [`Error::InvalidChaosTrigger`] if a trigger is out of range, checked
+    ///   before sending the request
     /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
     pub async fn put_set_chaos_triggers(
         &self,
         config: Option<ChaosTriggersConfiguration>,
     ) -> Result<ChaosTriggersResponse, Error> {
+        if let Some(config) = &config {
+            config.validate()?;
+        }
+
         let response = self
             .client
-            .put(format!("{}api/v1/chaos", self.url))
+            .put(self.endpoint("api/v1/chaos")?)
             .json(&config)
             .send()
             .await?;
-        Error::check_response(response)
-            .await?
-            .json()
-            .await
-            .map_err(Into::into)
+        Error::deserialize(Error::check_response(response).await?).await
+    }
+
+    #[cfg(feature = "chaos")]
+    /// #### Set Chaos triggers, returning the previous configuration
+    /// __GET__ and __PUT__ `/api/v1/chaos`
+    ///
+    /// Same as [`put_set_chaos_triggers`], but fetches the current
+    /// configuration first and returns `(before, after)`, e.g. for logging
+    /// Chaos changes.
+    ///
+    /// #### Errors:
+    /// - [`Error::InvalidChaosTrigger`] if a trigger is out of range, checked
+    ///   before sending any request
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    ///
+    /// [`put_set_chaos_triggers`]: crate::client::MailpitClient::put_set_chaos_triggers
+    pub async fn put_set_chaos_triggers_diff(
+        &self,
+        config: Option<ChaosTriggersConfiguration>,
+    ) -> Result<(ChaosTriggersResponse, ChaosTriggersResponse), Error> {
+        if let Some(config) = &config {
+            config.validate()?;
+        }
+
+        let before = self.get_chaos_triggers().await?;
+        let after = self.put_set_chaos_triggers(config).await?;
+        Ok((before, after))
     }
 
     /// #### Render message HTML part
@@ -674,6 +2198,9 @@ impl MailpitClient {
     /// link to the API provided they exist. Note that is the message
     /// does not contain a HTML part then an 404 error is returned.
     ///
+    /// See [`EmbedMode`] for the meaning of `embed`, `None` uses Mailpit's
+    /// default.
+    ///
     /// The ID can be set to `latest` to return the latest message.
     ///
     /// #### Errors:
@@ -682,13 +2209,14 @@ impl MailpitClient {
     pub async fn get_render_message_html_part(
         &self,
         id: &str,
-        embed: Option<bool>,
+        embed: Option<EmbedMode>,
     ) -> Result<String, Error> {
-        let mut builder = self.client.get(format!("{}view/{id}.html", self.url));
-
-        if let Some(embed) = embed {
-            builder = builder.query(&[("embed", embed as u8)]);
-        }
+        let builder = QueryParams::new()
+            .opt("embed", embed.map(EmbedMode::query_value))
+            .apply(
+                self.client
+                    .get(self.endpoint_segments(&["view", &format!("{id}.html")])),
+            );
 
         let response = builder.send().await?;
         Error::check_response(response)
@@ -712,7 +2240,7 @@ impl MailpitClient {
     pub async fn get_render_message_text_part(&self, id: &str) -> Result<String, Error> {
         let response = self
             .client
-            .get(format!("{}view/{id}.txt", self.url))
+            .get(self.endpoint_segments(&["view", &format!("{id}.txt")]))
             .send()
             .await?;
         Error::check_response(response)
@@ -721,4 +2249,476 @@ impl MailpitClient {
             .await
             .map_err(Into::into)
     }
+
+    /// #### Render message HTML and text parts
+    /// __GET__ `/view/{ID}.html` and `/view/{ID}.txt`
+    ///
+    /// Renders both the message's HTML and text part. Messages without a
+    /// HTML part result in a [`RenderedMessage`] without `html`. See
+    /// [`get_render_message_html_part`] for the meaning of `embed`.
+    ///
+    /// The ID can be set to `latest` to return the latest message.
+    ///
+    /// #### Errors:
+    /// - __`400`__ - Server error will return with a 400 status code with the error message in the body
+    /// - __`404`__ - Not found error will return a 404 status code
+    ///
+    /// [`get_render_message_html_part`]: crate::client::MailpitClient::get_render_message_html_part
+    pub async fn get_rendered_message(
+        &self,
+        id: &str,
+        embed: Option<EmbedMode>,
+    ) -> Result<RenderedMessage, Error> {
+        let (html, text) = futures_util::join!(
+            self.get_render_message_html_part(id, embed),
+            self.get_render_message_text_part(id)
+        );
+
+        let html = match html {
+            Ok(html) => Some(html),
+            Err(Error::HttpFailure { status: 404, .. }) => None,
+            Err(e) => return Err(e),
+        };
+
+        Ok(RenderedMessage { html, text: text? })
+    }
+}
+
+impl AsRef<Url> for MailpitClient {
+    fn as_ref(&self) -> &Url {
+        &self.url
+    }
+}
+
+/// Builder to create a [`MailpitClient`].
+pub struct MailpitClientBuilder {
+    url: String,
+    pool_max_idle_per_host: Option<usize>,
+    pool_idle_timeout: Option<Duration>,
+    tcp_keepalive: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    user_agent: Option<String>,
+    headers: HeaderMap,
+    http2_prior_knowledge: bool,
+    preserve_read_status: bool,
+    default_page_limit: Option<usize>,
+    default_tz: Option<Tz>,
+    #[cfg(feature = "send")]
+    default_send_tags: Vec<String>,
+}
+
+impl MailpitClientBuilder {
+    /// Returns [`MailpitClientBuilder`] to create a [`MailpitClient`] for
+    /// the given `url`.
+    pub fn new(url: &str) -> Self {
+        MailpitClientBuilder {
+            url: url.to_string(),
+            pool_max_idle_per_host: None,
+            pool_idle_timeout: None,
+            tcp_keepalive: None,
+            connect_timeout: None,
+            timeout: None,
+            user_agent: None,
+            headers: HeaderMap::new(),
+            http2_prior_knowledge: false,
+            preserve_read_status: false,
+            default_page_limit: None,
+            default_tz: None,
+            #[cfg(feature = "send")]
+            default_send_tags: Vec::new(),
+        }
+    }
+
+    /// Maximum number of idle connections kept per host. Defaults to no
+    /// limit.
+    ///
+    /// This is a performance knob only, e.g. when sending many concurrent
+    /// requests to a single Mailpit instance.
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    /// Timeout after which idle connections are closed. Defaults to 90
+    /// seconds.
+    ///
+    /// This is a performance knob only, e.g. when sending many concurrent
+    /// requests to a single Mailpit instance.
+    pub fn pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Interval of TCP keepalive probes on open connections. Defaults to
+    /// no keepalive.
+    ///
+    /// Useful for clients living as long as a whole test session, e.g.
+    /// when polling Mailpit in a tight loop.
+    pub fn tcp_keepalive(mut self, interval: Duration) -> Self {
+        self.tcp_keepalive = Some(interval);
+        self
+    }
+
+    /// Timeout for establishing a connection, including the TLS handshake.
+    /// Defaults to no timeout.
+    ///
+    /// Only bounds connecting, not waiting for the response, so an
+    /// unreachable host fails fast while slow endpoints like the link
+    /// check still have time to respond.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Timeout for a whole request, from connecting until the response
+    /// body is read. Defaults to no timeout.
+    ///
+    /// An elapsed timeout is returned as [`Error::ReqwestFailure`].
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Authenticate every request with Basic Authentication.
+    pub fn basic_auth(mut self, username: &str, password: &str) -> Self {
+        let encoded = BASE64_STANDARD.encode(format!("{username}:{password}"));
+        let mut value = HeaderValue::from_str(&format!("Basic {encoded}")).unwrap();
+        value.set_sensitive(true);
+        self.headers.insert(header::AUTHORIZATION, value);
+        self
+    }
+
+    /// `User-Agent` header sent with every request. Defaults to none.
+    ///
+    /// An invalid header value fails [`MailpitClientBuilder::build`] with
+    /// [`Error::ReqwestFailure`].
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = Some(user_agent.to_string());
+        self
+    }
+
+    /// Header sent with every request, e.g. to authenticate against a
+    /// reverse proxy in front of Mailpit. Replaces a header with the same
+    /// `name` set before, including the one set by
+    /// [`MailpitClientBuilder::basic_auth`].
+    pub fn default_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    /// Only use HTTP/2, without negotiating the protocol first. Defaults
+    /// to `false`.
+    ///
+    /// Requests fail if the endpoint does not speak HTTP/2, so only enable
+    /// this when Mailpit is reachable via an HTTP/2-capable endpoint, e.g.
+    /// a reverse proxy.
+    pub fn http2_prior_knowledge(mut self, enabled: bool) -> Self {
+        self.http2_prior_knowledge = enabled;
+        self
+    }
+
+    /// Keep unread messages unread when fetching their summary. Defaults
+    /// to `false`.
+    ///
+    /// Mailpit marks a message as read whenever its summary is fetched,
+    /// e.g. by [`get_message_summary`]. With this enabled the client looks
+    /// up the read status of the message by its ID first, see
+    /// [`get_message_light`], and marks it as unread again afterwards. This
+    /// costs up to three extra requests per fetch, or two for `latest`.
+    ///
+    /// [`get_message_summary`]: crate::client::MailpitClient::get_message_summary
+    /// [`get_message_light`]: crate::client::MailpitClient::get_message_light
+    pub fn preserve_read_status(mut self, enabled: bool) -> Self {
+        self.preserve_read_status = enabled;
+        self
+    }
+
+    /// Limit used by [`get_list_messages`] and [`get_search_messages`] if
+    /// no `limit` is passed. Defaults to none, leaving the limit to the
+    /// server, which returns 50 messages.
+    ///
+    /// [`get_list_messages`]: crate::client::MailpitClient::get_list_messages
+    /// [`get_search_messages`]: crate::client::MailpitClient::get_search_messages
+    pub fn default_page_limit(mut self, limit: usize) -> Self {
+        self.default_page_limit = Some(limit);
+        self
+    }
+
+    /// Time zone sent by [`get_search_messages`], [`put_set_read_status`]
+    /// and [`delete_messages_by_search`] if no `tz` is passed. Defaults to
+    /// none, leaving the time zone to the server.
+    ///
+    /// [`get_search_messages`]: crate::client::MailpitClient::get_search_messages
+    /// [`put_set_read_status`]: crate::client::MailpitClient::put_set_read_status
+    /// [`delete_messages_by_search`]: crate::client::MailpitClient::delete_messages_by_search
+    pub fn default_tz(mut self, tz: Tz) -> Self {
+        self.default_tz = Some(tz);
+        self
+    }
+
+    #[cfg(feature = "send")]
+    /// Tag added to every message sent with [`post_send_message`], in
+    /// addition to the tags of the message itself. Can be called multiple
+    /// times to add multiple tags.
+    ///
+    /// [`post_send_message`]: crate::client::MailpitClient::post_send_message
+    pub fn default_send_tag(mut self, tag: impl Into<String>) -> Self {
+        self.default_send_tags.push(tag.into());
+        self
+    }
+
+    /// Try building a [`MailpitClient`] from the set values.
+    pub fn build(self) -> Result<MailpitClient, Error> {
+        let url = parse_base_url(&self.url)?;
+
+        let mut builder = Client::builder().default_headers(self.headers.clone());
+        if let Some(user_agent) = self.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+        if let Some(max) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+        if let Some(timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(timeout);
+        }
+        if let Some(interval) = self.tcp_keepalive {
+            builder = builder.tcp_keepalive(interval);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if self.http2_prior_knowledge {
+            builder = builder.http2_prior_knowledge();
+        }
+
+        Ok(MailpitClient {
+            url,
+            client: builder.build()?,
+            preserve_read_status: self.preserve_read_status,
+            default_page_limit: self.default_page_limit,
+            default_tz: self.default_tz,
+            #[cfg(feature = "events")]
+            headers: self.headers,
+            #[cfg(feature = "send")]
+            default_send_tags: self.default_send_tags,
+        })
+    }
+}
+
+#[cfg(feature = "checks")]
+/// Turn a check failing because `feature` is disabled at runtime into
+/// `None`, keeping any other error.
+fn optional_check<T>(result: Result<T, Error>, feature: &str) -> Result<Option<T>, Error> {
+    match result {
+        Ok(check) => Ok(Some(check)),
+        Err(e) if e.is_disabled(feature) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Search for the `Message-ID` header `message_id`, with or without
+/// surrounding angle brackets.
+fn message_id_search(message_id: &str) -> Result<String, Error> {
+    let message_id = message_id
+        .trim()
+        .trim_start_matches('<')
+        .trim_end_matches('>');
+    if message_id.is_empty() || message_id.contains('"') {
+        return Err(Error::InvalidSearchQuery(format!(
+            "invalid Message-ID `{message_id}`"
+        )));
+    }
+
+    Ok(format!("message-id:\"{message_id}\""))
+}
+
+/// Page through all messages returned by `fetch`, which is called with the
+/// offset and limit of each page.
+fn paginate<'a, F, Fut>(
+    page_size: usize,
+    fetch: F,
+) -> impl Stream<Item = Result<MessageInfo, Error>> + 'a
+where
+    F: Fn(usize, usize) -> Fut + 'a,
+    Fut: Future<Output = Result<MessagesSummary, Error>> + 'a,
+{
+    stream::try_unfold(Some(0), move |start| {
+        let page = start.map(|start| (start, fetch(start, page_size)));
+        async move {
+            let Some((start, page)) = page else {
+                return Ok::<_, Error>(None);
+            };
+
+            let page = page.await?;
+            let next = start + page.messages.len();
+            let next = (!page.messages.is_empty() && next < page.messages_count).then_some(next);
+
+            Ok(Some((
+                stream::iter(page.messages.into_iter().map(Ok)),
+                next,
+            )))
+        }
+    })
+    .try_flatten()
+}
+
+/// Run `f` for all `keys` with up to [`BULK_CONCURRENCY`] requests in
+/// flight, handling failures according to `mode`.
+async fn bulk<'a, T, F, Fut>(
+    keys: &[&'a str],
+    mode: BulkMode,
+    mut f: F,
+) -> Result<BulkOutcome<T>, Error>
+where
+    F: FnMut(&'a str) -> Fut,
+    Fut: Future<Output = Result<T, Error>>,
+{
+    let mut results = stream::iter(keys.iter().copied())
+        .map(|key| {
+            let result = f(key);
+            async move { (key, result.await) }
+        })
+        .buffered(BULK_CONCURRENCY);
+
+    let mut outcome = BulkOutcome {
+        succeeded: Vec::new(),
+        failed: Vec::new(),
+    };
+    while let Some((key, result)) = results.next().await {
+        match (result, mode) {
+            (Ok(value), _) => outcome.succeeded.push(value),
+            (Err(e), BulkMode::CollectErrors) => outcome.failed.push((key.to_string(), e)),
+            (Err(e), BulkMode::FailFast) => return Err(e),
+        }
+    }
+    Ok(outcome)
+}
+
+/// Parse `url`, making sure its path ends with a `/` so that relative
+/// endpoint paths are joined onto it instead of replacing its last segment.
+fn parse_base_url(url: &str) -> Result<Url, Error> {
+    let mut url = Url::parse(url)?;
+    if url.cannot_be_a_base() {
+        return Err(ParseError::RelativeUrlWithCannotBeABaseBase.into());
+    }
+    if !url.path().ends_with('/') {
+        let path = format!("{}/", url.path());
+        url.set_path(&path);
+    }
+    Ok(url)
+}
+
+/// Returns `name`, or `name` with a numeric suffix if it is already
+/// contained in `names`, and adds the returned name to `names`.
+#[cfg(feature = "zip")]
+fn unique_file_name(name: &str, names: &mut std::collections::HashSet<String>) -> String {
+    let mut unique = name.to_string();
+    let (stem, extension) = match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, Some(extension)),
+        _ => (name, None),
+    };
+
+    let mut n = 1;
+    while names.contains(&unique) {
+        unique = match extension {
+            Some(extension) => format!("{stem} ({n}).{extension}"),
+            None => format!("{stem} ({n})"),
+        };
+        n += 1;
+    }
+
+    names.insert(unique.clone());
+    unique
+}
+
+/// Parse the header section of a raw message into `(name, value)` pairs,
+/// keeping their order and unfolding folded values.
+fn parse_headers(source: &str) -> Vec<(String, String)> {
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for line in source.lines() {
+        if line.is_empty() {
+            break;
+        }
+
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push_str(line);
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim_start().to_string()));
+        }
+    }
+    headers
+}
+
+/// Find the MIME entity with the dot-separated, 1-based `part_id` in the
+/// raw message `source`, returning it including its headers.
+fn find_part<'a>(source: &'a str, part_id: &str) -> Option<&'a str> {
+    let mut entity = source;
+    for index in part_id.split('.') {
+        let index: usize = index.parse().ok()?;
+        let content_type = parse_headers(entity)
+            .into_iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("Content-Type"))?
+            .1;
+        let boundary = boundary(&content_type)?;
+        let (_, body) = split_entity(entity);
+        entity = split_multipart(body, boundary)
+            .into_iter()
+            .nth(index.checked_sub(1)?)?;
+    }
+    Some(entity)
+}
+
+/// Returns the `boundary` parameter of a multipart `Content-Type` value.
+fn boundary(content_type: &str) -> Option<&str> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case("boundary")
+            .then(|| value.trim().trim_matches('"'))
+    })
+}
+
+/// Split a MIME entity into its header section and body.
+fn split_entity(entity: &str) -> (&str, &str) {
+    let separator = ["\r\n\r\n", "\n\n"]
+        .into_iter()
+        .filter_map(|separator| entity.find(separator).map(|i| (i, separator.len())))
+        .min();
+    match separator {
+        Some((i, len)) => (&entity[..i], &entity[i + len..]),
+        None => (entity, ""),
+    }
+}
+
+/// Split a multipart body into its parts, ignoring preamble and epilogue.
+fn split_multipart<'a>(body: &'a str, boundary: &str) -> Vec<&'a str> {
+    let delimiter = format!("--{boundary}");
+    let mut parts = Vec::new();
+    let mut start = None;
+    let mut offset = 0;
+    for line in body.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+
+        let Some(rest) = line.trim_end().strip_prefix(&delimiter) else {
+            continue;
+        };
+        if !rest.is_empty() && rest != "--" {
+            continue;
+        }
+        if let Some(start) = start {
+            parts.push(&body[start..line_start]);
+        }
+        if rest == "--" {
+            break;
+        }
+        start = Some(offset);
+    }
+    parts
 }
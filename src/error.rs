@@ -1,5 +1,8 @@
-use reqwest::Error as ReqwestError;
-use serde::Deserialize;
+use std::time::Duration;
+
+use reqwest::{Error as ReqwestError, StatusCode};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Error as SerdeJsonError;
 use thiserror::Error;
 use url::ParseError;
 
@@ -15,20 +18,80 @@ pub enum Error {
         body: Option<MailpitError>,
         text: String,
     },
+    #[cfg(feature = "send")]
     #[error(
         "Trying to build an attachment without a `filename`. Make sure you set one on the builder."
     )]
     AttachmentFilenameMissing,
+    #[cfg(feature = "send")]
     #[error(
         "Trying to build an attachment without `content`. Make sure you set content on the builder."
     )]
     AttachmentContentMissing,
+    #[cfg(feature = "chaos")]
+    #[error(
+        "Invalid Chaos trigger: `error_code` must range from 400 to 599 and `probability` from 0 to 100, got {error_code} and {probability}."
+    )]
+    InvalidChaosTrigger { error_code: i32, probability: i32 },
+    #[error("Invalid search query: {0}")]
+    InvalidSearchQuery(String),
+    #[error("Invalid email address: `{0}`")]
+    InvalidAddress(String),
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+    #[error("Unexpected Mailpit response: {body}")]
+    UnexpectedResponse { body: String },
+    #[error("Mailpit responded with an empty body")]
+    EmptyResponse,
+    #[error("Expected a single message matching `{query}`, found {count}")]
+    MatchCount { query: String, count: usize },
+    #[cfg(feature = "send")]
+    #[error("Message relaying is not configured on the Mailpit server")]
+    RelayNotConfigured,
+    #[cfg(feature = "send")]
+    #[error("Sending message {index} failed: {source}")]
+    SendManyFailed { index: usize, source: Box<Error> },
+    #[error("Message has no part `{part_id}`")]
+    PartNotFound { part_id: String },
+    #[error("Timed out after {0:?}")]
+    Timeout(Duration),
+    #[error("Mailbox still contains {remaining} messages after draining")]
+    DrainIncomplete { remaining: usize },
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] SerdeJsonError),
+    #[cfg(feature = "events")]
+    #[error("WebSocket error: {0}")]
+    WebSocket(#[from] tokio_tungstenite::tungstenite::Error),
+    #[cfg(feature = "zip")]
+    #[error("Failed to build ZIP archive: {0}")]
+    Zip(#[from] zip::result::ZipError),
+    #[error("Failed to deserialize response at `{path}`: {source}. Body: {body_snippet}")]
+    Deserialize {
+        path: String,
+        source: SerdeJsonError,
+        body_snippet: String,
+    },
 }
 
 impl Error {
-    pub(crate) async fn check_response(
-        response: reqwest::Response,
-    ) -> Result<reqwest::Response, Error> {
+    /// HTTP status code of the response that caused this error, if any.
+    pub fn status_code(&self) -> Option<StatusCode> {
+        match self {
+            Error::HttpFailure { status, .. } => StatusCode::from_u16(*status).ok(),
+            Error::ReqwestFailure(e) => e.status(),
+            _ => None,
+        }
+    }
+
+    /// Check the status of `response`, returning [`Error::HttpFailure`]
+    /// for any non-success status.
+    ///
+    /// Together with [`Error::handle_response`] this is the supported way to
+    /// reuse the error mapping of this crate for requests to endpoints it
+    /// does not wrap yet.
+    pub async fn check_response(response: reqwest::Response) -> Result<reqwest::Response, Error> {
         if !response.status().is_success() {
             let status = response.status().into();
             let text = response.text().await?;
@@ -41,10 +104,138 @@ impl Error {
 
         Ok(response)
     }
+
+    #[cfg(feature = "send")]
+    /// Turn a `400` response stating that message relaying is disabled
+    /// into [`Error::RelayNotConfigured`], keeping any other error as is.
+    pub(crate) fn relay_not_configured(self) -> Error {
+        if self.is_disabled("relay") {
+            return Error::RelayNotConfigured;
+        }
+        self
+    }
+
+    /// Whether this is a `400` response stating that the Mailpit `feature`
+    /// is disabled at runtime.
+    #[cfg(any(feature = "chaos", feature = "checks", feature = "send"))]
+    pub(crate) fn is_disabled(&self, feature: &str) -> bool {
+        let Error::HttpFailure {
+            status: 400, text, ..
+        } = self
+        else {
+            return false;
+        };
+
+        let text = text.to_lowercase();
+        text.contains(feature)
+            && ["disabled", "not enabled", "not configured"]
+                .iter()
+                .any(|reason| text.contains(reason))
+    }
+
+    /// Check the status of `response` and deserialize its JSON body, the same
+    /// way all wrapped endpoints do.
+    ///
+    /// ```no_run
+    /// # async fn run() -> Result<(), mailpit_client::error::Error> {
+    /// use mailpit_client::{error::Error, models::MessageSummary};
+    ///
+    /// let response = reqwest::get("http://localhost:8025/api/v1/message/latest").await?;
+    /// let summary: MessageSummary = Error::handle_response(response).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn handle_response<T: DeserializeOwned>(
+        response: reqwest::Response,
+    ) -> Result<T, Error> {
+        Self::deserialize(Self::check_response(response).await?).await
+    }
+
+    /// Expect the body of `response` to be a literal `ok` or empty, returning
+    /// [`Error::UnexpectedResponse`] for any other body.
+    pub(crate) async fn expect_ok(response: reqwest::Response) -> Result<bool, Error> {
+        let body = response.text().await?;
+        if body != "ok" && !body.trim().is_empty() {
+            return Err(Error::UnexpectedResponse { body });
+        }
+
+        Ok(true)
+    }
+
+    /// Deserialize the JSON body of `response`. On failure the error
+    /// contains the path of the offending field and the beginning of
+    /// the body to make API drift debuggable.
+    pub(crate) async fn deserialize<T: DeserializeOwned>(
+        response: reqwest::Response,
+    ) -> Result<T, Error> {
+        let text = response.text().await?;
+        Self::deserialize_str(&text)
+    }
+
+    pub(crate) fn deserialize_str<T: DeserializeOwned>(text: &str) -> Result<T, Error> {
+        if text.trim().is_empty() {
+            return Err(Error::EmptyResponse);
+        }
+
+        let deserializer = &mut serde_json::Deserializer::from_str(text);
+        serde_path_to_error::deserialize(deserializer).map_err(|e| Error::Deserialize {
+            path: e.path().to_string(),
+            source: e.into_inner(),
+            body_snippet: body_snippet(text).to_string(),
+        })
+    }
+
+    /// Deserialize the JSON body of a potentially large `response`. Uses
+    /// `simd-json` if the `simd-json` feature is enabled, otherwise this
+    /// is the same as [`Error::deserialize`].
+    pub(crate) async fn deserialize_large<T: DeserializeOwned>(
+        response: reqwest::Response,
+    ) -> Result<T, Error> {
+        #[cfg(feature = "simd-json")]
+        {
+            let bytes = response.bytes().await?;
+            // simd-json uses the buffer as scratch space, keep `bytes` intact.
+            let mut buffer = bytes.to_vec();
+            match simd_json::serde::from_slice(&mut buffer) {
+                Ok(value) => Ok(value),
+                // Deserialize again to get a detailed error.
+                Err(_) => Self::deserialize_str(&String::from_utf8_lossy(&bytes)),
+            }
+        }
+        #[cfg(not(feature = "simd-json"))]
+        {
+            Self::deserialize(response).await
+        }
+    }
 }
 
-#[derive(Debug, Deserialize)]
+/// Maximum number of bytes of a response body kept in [`Error::Deserialize`].
+const BODY_SNIPPET_LEN: usize = 512;
+
+fn body_snippet(text: &str) -> &str {
+    if text.len() <= BODY_SNIPPET_LEN {
+        return text;
+    }
+
+    let mut end = BODY_SNIPPET_LEN;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 pub struct MailpitError {
     pub error: String,
 }
+
+impl MailpitError {
+    /// Error response with the given `message`, e.g. to build fixtures
+    /// simulating Mailpit errors.
+    pub fn new(message: impl Into<String>) -> Self {
+        MailpitError {
+            error: message.into(),
+        }
+    }
+}
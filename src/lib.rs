@@ -1,8 +1,24 @@
+mod api;
 mod client;
 pub mod error;
 pub mod models;
+mod params;
+pub mod search;
 
-pub use client::MailpitClient;
+pub use api::MailpitApi;
+pub use client::{MailpitClient, MailpitClientBuilder};
 
 pub use bytes::Bytes;
 pub use chrono_tz::Tz;
+
+/// Re-exports of the most commonly used types, so that
+/// `use mailpit_client::prelude::*;` is enough for typical code.
+pub mod prelude {
+    #[cfg(feature = "send")]
+    pub use crate::models::{Attachment, SendMessage};
+    pub use crate::{
+        MailpitApi, MailpitClient, Tz,
+        error::Error,
+        models::{AddressObject, MessageInfo, MessageSummary, MessagesSummary},
+    };
+}
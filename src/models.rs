@@ -1,8 +1,14 @@
-use std::collections::HashMap;
+#[cfg(feature = "send")]
+use std::collections::BTreeMap;
+use std::{cmp::Ordering, collections::HashMap, ops::Deref, str::FromStr, time::Duration};
 
+#[cfg(feature = "send")]
 use base64::{Engine, prelude::BASE64_STANDARD};
 use chrono::{DateTime, Utc};
+#[cfg(feature = "checks")]
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "checks")]
 use url::Url;
 
 use crate::error::Error;
@@ -29,6 +35,21 @@ pub struct ApplicationInformation {
     pub version: String,
 }
 
+impl ApplicationInformation {
+    /// Change of the message totals and runtime counters since `previous`,
+    /// see [`RuntimeStats::delta`].
+    ///
+    /// The message totals are `None` if they are lower than in `previous`,
+    /// e.g. because messages were deleted in between.
+    pub fn delta(&self, previous: &ApplicationInformation) -> InfoDelta {
+        InfoDelta {
+            messages: self.messages.checked_sub(previous.messages),
+            unread: self.unread.checked_sub(previous.unread),
+            ..self.runtime_stats.delta(&previous.runtime_stats)
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 /// Runtime statistics
@@ -53,6 +74,150 @@ pub struct RuntimeStats {
     pub uptime: usize,
 }
 
+impl RuntimeStats {
+    /// Change of the runtime counters since `previous`, e.g. to report
+    /// the messages accepted between two polls of a monitoring loop.
+    ///
+    /// The counters start at zero when Mailpit restarts, so a counter lower
+    /// than in `previous`, or a lower uptime, results in `None` for the
+    /// affected deltas instead of a bogus value. The message totals are not
+    /// part of the runtime statistics and always `None`, use
+    /// [`ApplicationInformation::delta`] to include them.
+    pub fn delta(&self, previous: &RuntimeStats) -> InfoDelta {
+        let restarted = self.uptime < previous.uptime;
+        let delta = |current: usize, previous: usize| {
+            (!restarted)
+                .then(|| current.checked_sub(previous))
+                .flatten()
+        };
+
+        InfoDelta {
+            messages: None,
+            unread: None,
+            messages_deleted: delta(self.messages_deleted, previous.messages_deleted),
+            smtp_accepted: delta(self.smtp_accepted, previous.smtp_accepted),
+            smtp_accepted_size: delta(self.smtp_accepted_size, previous.smtp_accepted_size),
+            smtp_ignored: delta(self.smtp_ignored, previous.smtp_ignored),
+            smtp_rejected: delta(self.smtp_rejected, previous.smtp_rejected),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Change of the [`ApplicationInformation`] message totals and
+/// [`RuntimeStats`] counters between two polls, `None` if a value is lower
+/// than before, e.g. because a counter was reset by a Mailpit restart in
+/// between
+pub struct InfoDelta {
+    /// Total number of messages in the database
+    pub messages: Option<usize>,
+    /// Total number of unread messages in the database
+    pub unread: Option<usize>,
+    /// Messages deleted
+    pub messages_deleted: Option<usize>,
+    /// Accepted SMTP messages
+    pub smtp_accepted: Option<usize>,
+    /// Size of the accepted SMTP messages in bytes
+    pub smtp_accepted_size: Option<usize>,
+    /// Ignored SMTP messages
+    pub smtp_ignored: Option<usize>,
+    /// Rejected SMTP messages
+    pub smtp_rejected: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Snapshot of the mailbox and the Mailpit instance, see
+/// [`MailpitClient::stats`]
+///
+/// [`MailpitClient::stats`]: crate::client::MailpitClient::stats
+pub struct MailboxStats {
+    /// Total number of messages in the mailbox
+    pub total: usize,
+    /// Total number of unread messages in the mailbox
+    pub unread: usize,
+    /// Tags and message totals per tag
+    pub tags: HashMap<String, usize>,
+    /// Database size in bytes
+    pub database_size: usize,
+    /// Current memory usage in bytes
+    pub memory: usize,
+    /// Time since Mailpit was started
+    pub uptime: Duration,
+    /// Current Mailpit version
+    pub version: String,
+}
+
+#[cfg(feature = "events")]
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(try_from = "RawEvent")]
+/// Event broadcast by Mailpit, see [`MailpitClient::subscribe_events`].
+///
+/// [`MailpitClient::subscribe_events`]: crate::client::MailpitClient::subscribe_events
+pub enum EventMessage {
+    /// A new message was received
+    NewMessage(Box<MessageInfo>),
+    /// A message was updated, e.g. its read status or tags
+    Update(serde_json::Value),
+    /// Messages with the given IDs were deleted
+    DeleteMessages(Vec<String>),
+    /// All messages were deleted
+    TruncateMessages,
+    /// Any other event, e.g. updated mailbox statistics
+    Other {
+        /// Event type
+        kind: String,
+        /// Event data
+        data: serde_json::Value,
+    },
+}
+
+#[cfg(feature = "events")]
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawEvent {
+    #[serde(rename = "Type")]
+    kind: String,
+    #[serde(default)]
+    data: serde_json::Value,
+}
+
+#[cfg(feature = "events")]
+#[derive(Deserialize)]
+struct DeletedIds {
+    #[serde(rename = "ID")]
+    id: Option<String>,
+    #[serde(rename = "IDs")]
+    ids: Option<Vec<String>>,
+}
+
+#[cfg(feature = "events")]
+impl TryFrom<RawEvent> for EventMessage {
+    type Error = serde_json::Error;
+
+    fn try_from(event: RawEvent) -> Result<Self, Self::Error> {
+        Ok(match event.kind.as_str() {
+            "new" => EventMessage::NewMessage(serde_json::from_value(event.data)?),
+            "update" => EventMessage::Update(event.data),
+            "delete" => {
+                let deleted: DeletedIds = serde_json::from_value(event.data)?;
+                EventMessage::DeleteMessages(
+                    deleted
+                        .ids
+                        .unwrap_or_default()
+                        .into_iter()
+                        .chain(deleted.id)
+                        .collect(),
+                )
+            }
+            "truncate" => EventMessage::TruncateMessages,
+            _ => EventMessage::Other {
+                kind: event.kind,
+                data: event.data,
+            },
+        })
+    }
+}
+
 #[derive(Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 /// Web UI configuration response
@@ -112,18 +277,64 @@ pub struct MessagesSummary {
     pub unread: usize,
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
+impl MessagesSummary {
+    /// Whether the messages matching the query, i.e. [`messages_count`] and
+    /// [`messages_unread`], equal `total` and `unread`.
+    ///
+    /// Use [`MessagesSummary::mailbox_matches_expected`] to check the whole
+    /// mailbox regardless of the query instead.
+    ///
+    /// ```
+    /// # use mailpit_client::models::MessagesSummary;
+    /// let summary: MessagesSummary = serde_json::from_str(r#"{
+    ///   "messages": [],
+    ///   "messages_count": 2,
+    ///   "messages_unread": 1,
+    ///   "start": 0,
+    ///   "tags": [],
+    ///   "total": 10,
+    ///   "unread": 4
+    /// }"#).unwrap();
+    ///
+    /// assert!(summary.matches_expected(2, 1));
+    /// assert!(summary.mailbox_matches_expected(10, 4));
+    /// assert!(!summary.matches_expected(10, 4));
+    /// ```
+    ///
+    /// [`messages_count`]: MessagesSummary::messages_count
+    /// [`messages_unread`]: MessagesSummary::messages_unread
+    pub fn matches_expected(&self, total: usize, unread: usize) -> bool {
+        self.messages_count == total && self.messages_unread == unread
+    }
+
+    /// Whether the whole mailbox, i.e. [`total`] and [`unread`], contains
+    /// `total` messages of which `unread` are unread, regardless of the
+    /// query.
+    ///
+    /// [`total`]: MessagesSummary::total
+    /// [`unread`]: MessagesSummary::unread
+    pub fn mailbox_matches_expected(&self, total: usize, unread: usize) -> bool {
+        self.total == total && self.unread == unread
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
 #[serde(rename_all = "PascalCase")]
+/// Fields shared by [`MessageInfo`] and [`MessageSummary`], both deref to
+/// their base so e.g. `message.subject` can be used directly.
 pub struct MessageBase<T> {
     /// Message attachments
     pub attachments: T,
     /// Bcc addresses
+    #[serde(serialize_with = "received_address::serialize_opt_vec")]
     pub bcc: Option<Vec<AddressObject>>,
     /// Cc addresses
+    #[serde(serialize_with = "received_address::serialize_opt_vec")]
     pub cc: Option<Vec<AddressObject>>,
     /// __Address represents a single mail address.:__ An address such as
     /// "Barry Gibbs bg@example.com" is represented as Address{Name:
     /// "Barry Gibbs", Address: "bg@example.com"}.
+    #[serde(serialize_with = "received_address::serialize")]
     pub from: AddressObject,
     /// Database ID
     #[serde(rename = "ID")]
@@ -132,6 +343,7 @@ pub struct MessageBase<T> {
     #[serde(rename = "MessageID")]
     pub message_id: String,
     /// ReplyTo addresses
+    #[serde(serialize_with = "received_address::serialize_vec")]
     pub reply_to: Vec<AddressObject>,
     /// Message size in bytes
     pub size: usize,
@@ -140,13 +352,66 @@ pub struct MessageBase<T> {
     /// Message tags
     pub tags: Vec<String>,
     /// To addresses
+    #[serde(serialize_with = "received_address::serialize_vec")]
     pub to: Vec<AddressObject>,
     /// Username used for authentication (if provided) with the SMTP or
     /// Send API
     pub username: String,
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
+/// Serialize the addresses of received messages with an `Address` field,
+/// like Mailpit returns them, instead of the `Email` field [`AddressObject`]
+/// uses for the Send API. This keeps serialized messages readable as
+/// [`MessageInfo`] and [`MessageSummary`] again.
+mod received_address {
+    use serde::{Serialize, Serializer};
+
+    use super::AddressObject;
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Received<'a> {
+        address: &'a str,
+        name: &'a Option<String>,
+    }
+
+    impl<'a> From<&'a AddressObject> for Received<'a> {
+        fn from(address: &'a AddressObject) -> Self {
+            Received {
+                address: &address.address,
+                name: &address.name,
+            }
+        }
+    }
+
+    pub(super) fn serialize<S: Serializer>(
+        address: &AddressObject,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        Received::from(address).serialize(serializer)
+    }
+
+    pub(super) fn serialize_vec<S: Serializer>(
+        addresses: &[AddressObject],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(addresses.iter().map(Received::from))
+    }
+
+    pub(super) fn serialize_opt_vec<S: Serializer>(
+        addresses: &Option<Vec<AddressObject>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match addresses {
+            Some(addresses) => {
+                serializer.serialize_some(&addresses.iter().map(Received::from).collect::<Vec<_>>())
+            }
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 pub struct MessageInfo {
     #[serde(flatten)]
@@ -162,6 +427,64 @@ pub struct MessageInfo {
 }
 
 impl MessageInfo {
+    /// Compare two messages chronologically by their received date &
+    /// time, using the database ID as tiebreaker.
+    ///
+    /// This is a comparator rather than an [`Ord`] implementation, since
+    /// equality of [`MessageInfo`] still considers all fields.
+    pub fn cmp_by_date(&self, other: &MessageInfo) -> Ordering {
+        self.created
+            .cmp(&other.created)
+            .then_with(|| self.base.id.cmp(&other.base.id))
+    }
+
+    /// Sort `messages` chronologically, oldest first. See
+    /// [`MessageInfo::cmp_by_date`].
+    pub fn sort_by_date(messages: &mut [MessageInfo]) {
+        messages.sort_by(MessageInfo::cmp_by_date);
+    }
+
+    /// Date & time Mailpit received the message.
+    ///
+    /// Not to be confused with the `Date` header set by the sender, see
+    /// [`MessageSummary::sent_at`].
+    pub fn received_at(&self) -> DateTime<Utc> {
+        self.created
+    }
+
+    /// Message snippet with any residual HTML tags stripped and
+    /// whitespace collapsed, safe for display in logs or a console.
+    pub fn snippet_plain(&self) -> String {
+        let mut text = String::with_capacity(self.snippet.len());
+        let mut rest = self.snippet.as_str();
+        while let Some(start) = rest.find('<') {
+            text.push_str(&rest[..start]);
+            let tag = &rest[start + 1..];
+            let is_tag = tag.starts_with(|c: char| c.is_ascii_alphabetic() || c == '/' || c == '!');
+            match tag.find('>') {
+                Some(end) if is_tag => {
+                    let name = tag[..end]
+                        .trim_start_matches('/')
+                        .split(|c: char| !c.is_ascii_alphanumeric())
+                        .next()
+                        .unwrap_or_default();
+                    // Block level tags separate words, e.g. `foo<br>bar`.
+                    if BLOCK_TAGS.iter().any(|t| t.eq_ignore_ascii_case(name)) {
+                        text.push(' ');
+                    }
+                    rest = &tag[end + 1..];
+                }
+                _ => {
+                    text.push('<');
+                    rest = tag;
+                }
+            }
+        }
+        text.push_str(rest);
+
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
     /// Message attachments
     pub fn attachments(&self) -> usize {
         self.base.attachments
@@ -226,7 +549,21 @@ impl MessageInfo {
     }
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
+impl Deref for MessageInfo {
+    type Target = MessageBase<usize>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+/// HTML tags that separate words when stripped by
+/// [`MessageInfo::snippet_plain`].
+const BLOCK_TAGS: &[&str] = &[
+    "br", "p", "div", "li", "tr", "td", "th", "h1", "h2", "h3", "h4", "h5", "h6", "hr",
+];
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 /// Message data excluding physical attachments
 pub struct MessageSummary {
@@ -251,12 +588,178 @@ pub struct MessageSummary {
     pub text: String,
 }
 
+impl Deref for MessageSummary {
+    type Target = MessageBase<Vec<AttachmentInfo>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
 impl MessageSummary {
+    /// Date & time from the `Date` header set by the sender, or the date &
+    /// time Mailpit received the message if the header is missing.
+    ///
+    /// Not to be confused with the receive time listed by Mailpit, see
+    /// [`MessageInfo::received_at`].
+    pub fn sent_at(&self) -> DateTime<Utc> {
+        self.date
+    }
+
     /// Message attachments
     pub fn attachments(&self) -> &Vec<AttachmentInfo> {
         &self.base.attachments
     }
 
+    /// Number of message attachments, excluding inline attachments
+    pub fn attachment_count(&self) -> usize {
+        self.base.attachments.len()
+    }
+
+    /// Total size in bytes of all message attachments, excluding inline
+    /// attachments
+    ///
+    /// ```
+    /// # use mailpit_client::models::MessageSummary;
+    /// let message: MessageSummary = serde_json::from_str(r#"{
+    ///   "Attachments": [
+    ///     { "ContentID": "", "ContentType": "application/pdf", "FileName": "a.pdf", "PartID": "2", "Size": 1024 },
+    ///     { "ContentID": "", "ContentType": "text/csv", "FileName": "b.csv", "PartID": "3", "Size": 256 }
+    ///   ],
+    ///   "Date": "1970-01-01T00:00:00.000Z",
+    ///   "From": { "Address": "john@example.com", "Name": "John Doe" },
+    ///   "HTML": "<img src=\"cid:logo\" />",
+    ///   "ID": "database-id",
+    ///   "Inline": [
+    ///     { "ContentID": "logo", "ContentType": "image/png", "FileName": "logo.png", "PartID": "1.2", "Size": 512 }
+    ///   ],
+    ///   "ListUnsubscribe": { "Errors": "", "Header": "", "HeaderPost": "", "Links": [] },
+    ///   "MessageID": "message-id",
+    ///   "ReplyTo": [],
+    ///   "ReturnPath": "",
+    ///   "Size": 4096,
+    ///   "Subject": "string",
+    ///   "Tags": [],
+    ///   "Text": "string",
+    ///   "To": [],
+    ///   "Username": ""
+    /// }"#)?;
+    ///
+    /// assert_eq!(2, message.attachment_count());
+    /// assert_eq!(1280, message.attachments_total_size());
+    /// assert_eq!(1, message.inline_count());
+    /// assert_eq!(512, message.inline_total_size());
+    /// # Ok::<(), serde_json::Error>(())
+    /// ```
+    pub fn attachments_total_size(&self) -> usize {
+        self.base.attachments.iter().map(|a| a.size).sum()
+    }
+
+    /// Number of inline message attachments
+    pub fn inline_count(&self) -> usize {
+        self.inline.len()
+    }
+
+    /// Total size in bytes of all inline message attachments
+    pub fn inline_total_size(&self) -> usize {
+        self.inline.iter().map(|a| a.size).sum()
+    }
+
+    /// Inline message attachments keyed by their Content-ID, to resolve
+    /// `cid:` references of the HTML body. Attachments without Content-ID
+    /// are skipped.
+    ///
+    /// ```
+    /// # use mailpit_client::models::MessageSummary;
+    /// let message: MessageSummary = serde_json::from_str(r#"{
+    ///   "Attachments": [],
+    ///   "Date": "1970-01-01T00:00:00.000Z",
+    ///   "From": { "Address": "john@example.com", "Name": "John Doe" },
+    ///   "HTML": "<img src=\"cid:logo\" /><img src=\"cid:banner\" />",
+    ///   "ID": "database-id",
+    ///   "Inline": [
+    ///     { "ContentID": "logo", "ContentType": "image/png", "FileName": "logo.png", "PartID": "1.2", "Size": 512 },
+    ///     { "ContentID": "banner", "ContentType": "image/jpeg", "FileName": "banner.jpg", "PartID": "1.3", "Size": 2048 }
+    ///   ],
+    ///   "ListUnsubscribe": { "Errors": "", "Header": "", "HeaderPost": "", "Links": [] },
+    ///   "MessageID": "message-id",
+    ///   "ReplyTo": [],
+    ///   "ReturnPath": "",
+    ///   "Size": 4096,
+    ///   "Subject": "string",
+    ///   "Tags": [],
+    ///   "Text": "string",
+    ///   "To": [],
+    ///   "Username": ""
+    /// }"#)?;
+    ///
+    /// let inline = message.inline_map();
+    /// assert_eq!("1.2", inline["logo"].part_id);
+    /// assert_eq!("banner.jpg", inline["banner"].file_name);
+    /// assert!(!inline.contains_key("missing"));
+    /// # Ok::<(), serde_json::Error>(())
+    /// ```
+    pub fn inline_map(&self) -> HashMap<&str, &AttachmentInfo> {
+        self.inline
+            .iter()
+            .filter(|a| !a.content_id.is_empty())
+            .map(|a| (a.content_id.as_str(), a))
+            .collect()
+    }
+
+    /// Attachment or inline attachment with the given part ID
+    pub fn find_attachment(&self, part_id: &str) -> Option<&AttachmentInfo> {
+        self.base
+            .attachments
+            .iter()
+            .chain(&self.inline)
+            .find(|a| a.part_id == part_id)
+    }
+
+    /// All attachments and inline attachments, labeled with the list
+    /// they are listed in
+    ///
+    /// ```
+    /// # use mailpit_client::models::{AttachmentKind, MessageSummary};
+    /// let message: MessageSummary = serde_json::from_str(r#"{
+    ///   "Attachments": [
+    ///     { "ContentID": "", "ContentType": "application/pdf", "FileName": "a.pdf", "PartID": "2", "Size": 1024 }
+    ///   ],
+    ///   "Date": "1970-01-01T00:00:00.000Z",
+    ///   "From": { "Address": "john@example.com", "Name": "John Doe" },
+    ///   "HTML": "<img src=\"cid:logo\" />",
+    ///   "ID": "database-id",
+    ///   "Inline": [
+    ///     { "ContentID": "logo", "ContentType": "image/png", "FileName": "logo.png", "PartID": "1.2", "Size": 512 }
+    ///   ],
+    ///   "ListUnsubscribe": { "Errors": "", "Header": "", "HeaderPost": "", "Links": [] },
+    ///   "MessageID": "message-id",
+    ///   "ReplyTo": [],
+    ///   "ReturnPath": "",
+    ///   "Size": 4096,
+    ///   "Subject": "string",
+    ///   "Tags": [],
+    ///   "Text": "string",
+    ///   "To": [],
+    ///   "Username": ""
+    /// }"#)?;
+    ///
+    /// let parts = message.classified_parts();
+    /// assert_eq!(2, parts.len());
+    /// assert_eq!((AttachmentKind::Attachment, "a.pdf"), (parts[0].0, parts[0].1.file_name.as_str()));
+    /// assert_eq!((AttachmentKind::Inline, "logo.png"), (parts[1].0, parts[1].1.file_name.as_str()));
+    /// assert!(parts[1].1.is_inline());
+    /// # Ok::<(), serde_json::Error>(())
+    /// ```
+    pub fn classified_parts(&self) -> Vec<(AttachmentKind, &AttachmentInfo)> {
+        self.base
+            .attachments
+            .iter()
+            .map(|a| (AttachmentKind::Attachment, a))
+            .chain(self.inline.iter().map(|a| (AttachmentKind::Inline, a)))
+            .collect()
+    }
+
     /// Bcc addresses
     pub fn bcc(&self) -> Option<&Vec<AddressObject>> {
         self.base.bcc.as_ref()
@@ -327,7 +830,69 @@ pub struct AddressObject {
     pub name: Option<String>,
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
+impl From<&str> for AddressObject {
+    /// Address without name. Use [`str::parse`] to parse addresses like
+    /// `Jane Doe <jane@example.com>`.
+    fn from(address: &str) -> Self {
+        address.to_string().into()
+    }
+}
+
+impl From<String> for AddressObject {
+    /// Address without name.
+    fn from(address: String) -> Self {
+        AddressObject {
+            address,
+            name: None,
+        }
+    }
+}
+
+impl From<(&str, &str)> for AddressObject {
+    /// Address and name, in this order.
+    fn from((address, name): (&str, &str)) -> Self {
+        (address, Some(name)).into()
+    }
+}
+
+impl From<(&str, Option<&str>)> for AddressObject {
+    /// Address and optional name, in this order.
+    fn from((address, name): (&str, Option<&str>)) -> Self {
+        AddressObject {
+            address: address.to_string(),
+            name: name.map(str::to_string),
+        }
+    }
+}
+
+impl FromStr for AddressObject {
+    type Err = Error;
+
+    /// Parse an address like `jane@example.com`, `<jane@example.com>` or
+    /// `Jane Doe <jane@example.com>`. The name may be wrapped in double
+    /// quotes.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (name, address) = match s.strip_suffix('>').and_then(|s| s.rsplit_once('<')) {
+            Some((name, address)) => (name.trim(), address.trim()),
+            None => ("", s),
+        };
+        if !address.contains('@') || address.contains(char::is_whitespace) {
+            return Err(Error::InvalidAddress(s.to_string()));
+        }
+
+        let name = name
+            .strip_prefix('"')
+            .and_then(|name| name.strip_suffix('"'))
+            .unwrap_or(name);
+        Ok(AddressObject {
+            address: address.to_string(),
+            name: (!name.is_empty()).then(|| name.to_string()),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 /// Message attachment info
 pub struct AttachmentInfo {
@@ -345,7 +910,27 @@ pub struct AttachmentInfo {
     pub size: usize,
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
+impl AttachmentInfo {
+    /// Whether the attachment has a Content-ID, i.e. can be referenced
+    /// from the HTML body via `cid:`. Mailpit may list such parts as
+    /// regular attachments if they are not referenced, see
+    /// [`MessageSummary::classified_parts`] for the list a part is in.
+    pub fn is_inline(&self) -> bool {
+        !self.content_id.is_empty()
+    }
+}
+
+/// Whether a part is listed as attachment or inline attachment of a
+/// message, see [`MessageSummary::classified_parts`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttachmentKind {
+    /// Listed in [`MessageSummary::attachments`]
+    Attachment,
+    /// Listed in [`MessageSummary::inline`]
+    Inline,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 /// ListUnsubscribe contains a summary of List-Unsubscribe &
 /// List-Unsubscribe-Post headers including validation of the link
@@ -364,19 +949,69 @@ pub struct ListUnsubscribe {
 /// Message headers
 pub type MessageHeaders = HashMap<String, Vec<String>>;
 
+/// How a message got into Mailpit.
+///
+/// Mailpit does not store the ingestion source, so it is inferred from the
+/// message headers: Mailpit adds a `Received` header to every message
+/// accepted via SMTP, while messages created via the Send API have none.
+/// A Send API message with a manually set `Received` header is therefore
+/// reported as [`IngestSource::Smtp`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IngestSource {
+    /// Received via SMTP
+    Smtp,
+    /// Created via the HTTP Send API
+    SendApi,
+}
+
+impl IngestSource {
+    /// Infer the [`IngestSource`] from the message `headers`.
+    pub fn from_headers(headers: &MessageHeaders) -> IngestSource {
+        if headers
+            .keys()
+            .any(|key| key.eq_ignore_ascii_case("Received"))
+        {
+            IngestSource::Smtp
+        } else {
+            IngestSource::SendApi
+        }
+    }
+}
+
+#[cfg(feature = "send")]
 #[derive(Debug, Serialize, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 pub(crate) struct ReleaseMessageParams<'a> {
     pub(crate) to: &'a [&'a str],
 }
 
+#[cfg(feature = "send")]
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, rename_all = "PascalCase")]
+/// Details of a released message, see
+/// [`MailpitClient::post_release_message_verbose`].
+///
+/// [`MailpitClient::post_release_message_verbose`]: crate::client::MailpitClient::post_release_message_verbose
+pub struct ReleaseMessageResponse {
+    /// Message returned by the server, `ok` for current Mailpit versions
+    pub message: String,
+    /// Recipients the message was released to
+    pub to: Vec<String>,
+}
+
+#[cfg(feature = "send")]
+/// Message to send via the HTTP Send API.
+///
+/// Serialization is deterministic: fields are serialized in declaration
+/// order, which is kept alphabetical, and headers are sorted by name.
 #[derive(Debug, Serialize, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 pub struct SendMessage {
     /// Attachments
     pub attachments: Option<Vec<Attachment>>,
-    /// Bcc recipients email addresses only
-    pub bcc: Option<Vec<String>>,
+    /// Bcc recipients, only the email addresses are sent
+    #[serde(serialize_with = "serialize_addresses")]
+    pub bcc: Option<Vec<AddressObject>>,
     /// Cc recipients
     pub cc: Option<Vec<AddressObject>>,
     /// "From" recipient
@@ -384,8 +1019,8 @@ pub struct SendMessage {
     /// Message body (HTML)
     #[serde(rename = "HTML")]
     pub html: String,
-    /// Optional headers in {"key":"value"} format
-    pub headers: Option<HashMap<String, String>>,
+    /// Optional headers in {"key":"value"} format, sorted by key
+    pub headers: Option<BTreeMap<String, String>>,
     /// Optional Reply-To recipients
     pub reply_to: Option<Vec<AddressObject>>,
     /// Subject
@@ -398,6 +1033,198 @@ pub struct SendMessage {
     pub to: Vec<AddressObject>,
 }
 
+#[cfg(feature = "send")]
+impl SendMessage {
+    /// Returns [`SendMessageBuilder`] to create a [`SendMessage`] sent by `from`.
+    pub fn builder(from: impl Into<AddressObject>) -> SendMessageBuilder {
+        SendMessageBuilder::new(from)
+    }
+}
+
+/// Serialize `addresses` as plain email addresses, as the Send API only
+/// accepts those for Bcc recipients.
+#[cfg(feature = "send")]
+fn serialize_addresses<S: serde::Serializer>(
+    addresses: &Option<Vec<AddressObject>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    addresses
+        .as_ref()
+        .map(|addresses| addresses.iter().map(|a| &a.address).collect::<Vec<_>>())
+        .serialize(serializer)
+}
+
+#[cfg(feature = "send")]
+/// Builder to create a [`SendMessage`].
+#[derive(Debug)]
+pub struct SendMessageBuilder {
+    message: SendMessage,
+}
+
+#[cfg(feature = "send")]
+impl SendMessageBuilder {
+    /// Returns [`SendMessageBuilder`] to create a [`SendMessage`] sent by `from`.
+    pub fn new(from: impl Into<AddressObject>) -> Self {
+        let from = from.into();
+        SendMessageBuilder {
+            message: SendMessage {
+                attachments: None,
+                bcc: None,
+                cc: None,
+                from,
+                html: String::new(),
+                headers: None,
+                reply_to: None,
+                subject: String::new(),
+                tags: Vec::new(),
+                text: String::new(),
+                to: Vec::new(),
+            },
+        }
+    }
+
+    /// Add a "To" recipient.
+    pub fn to(mut self, to: impl Into<AddressObject>) -> Self {
+        self.message.to.push(to.into());
+        self
+    }
+
+    /// Add a Cc recipient.
+    pub fn cc(mut self, cc: impl Into<AddressObject>) -> Self {
+        self.message.cc.get_or_insert_default().push(cc.into());
+        self
+    }
+
+    /// Add a Bcc recipient. Only the email address is sent.
+    pub fn bcc(mut self, bcc: impl Into<AddressObject>) -> Self {
+        self.message.bcc.get_or_insert_default().push(bcc.into());
+        self
+    }
+
+    /// Add a Reply-To recipient.
+    ///
+    /// ```
+    /// # use mailpit_client::models::{AddressObject, SendMessage};
+    /// let message = SendMessage::builder(("john@example.com", "John Doe"))
+    ///     .to("jane@example.com")
+    ///     .reply_to("Secretary <sec@example.com>".parse::<AddressObject>()?)
+    ///     .build();
+    ///
+    /// assert_eq!("sec@example.com", message.reply_to.unwrap()[0].address);
+    /// # Ok::<(), mailpit_client::error::Error>(())
+    /// ```
+    pub fn reply_to(mut self, reply_to: impl Into<AddressObject>) -> Self {
+        self.message
+            .reply_to
+            .get_or_insert_default()
+            .push(reply_to.into());
+        self
+    }
+
+    /// Add a single "To" recipient. Same as [`SendMessageBuilder::to`].
+    pub fn to_address(self, to: impl Into<AddressObject>) -> Self {
+        self.to(to)
+    }
+
+    /// Add a single Cc recipient. Same as [`SendMessageBuilder::cc`].
+    pub fn cc_address(self, cc: impl Into<AddressObject>) -> Self {
+        self.cc(cc)
+    }
+
+    /// Add a single Reply-To recipient. Same as
+    /// [`SendMessageBuilder::reply_to`].
+    ///
+    /// ```
+    /// # use mailpit_client::models::{AddressObject, SendMessage};
+    /// let message = SendMessage::builder("john@example.com")
+    ///     .to_address("jane@example.com")
+    ///     .reply_to_address("Secretary <sec@example.com>".parse::<AddressObject>()?)
+    ///     .build();
+    ///
+    /// assert_eq!(Some("Secretary".to_string()), message.reply_to.unwrap()[0].name);
+    /// # Ok::<(), mailpit_client::error::Error>(())
+    /// ```
+    pub fn reply_to_address(self, reply_to: impl Into<AddressObject>) -> Self {
+        self.reply_to(reply_to)
+    }
+
+    /// Subject
+    pub fn subject(mut self, subject: impl Into<String>) -> Self {
+        self.message.subject = subject.into();
+        self
+    }
+
+    /// Message body (text)
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.message.text = text.into();
+        self
+    }
+
+    /// Message body (HTML)
+    pub fn html(mut self, html: impl Into<String>) -> Self {
+        self.message.html = html.into();
+        self
+    }
+
+    /// Add a Mailpit tag.
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.message.tags.push(tag.into());
+        self
+    }
+
+    /// Add an [`Attachment`].
+    pub fn attachment(mut self, attachment: Attachment) -> Self {
+        self.message
+            .attachments
+            .get_or_insert_default()
+            .push(attachment);
+        self
+    }
+
+    /// Set a header, replacing any previous value of the same header.
+    pub fn header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.message
+            .headers
+            .get_or_insert_default()
+            .insert(key.into(), value.into());
+        self
+    }
+
+    /// Mark the message as a reply to `message`.
+    ///
+    /// Sets the `In-Reply-To` header to the Message-ID of `message` and
+    /// appends it to the `References` header, so the reply is threaded
+    /// with the original message.
+    pub fn in_reply_to(self, message: &MessageSummary) -> Self {
+        let message_id = message.message_id();
+        let message_id = format!(
+            "<{}>",
+            message_id
+                .strip_prefix('<')
+                .and_then(|id| id.strip_suffix('>'))
+                .unwrap_or(message_id)
+        );
+        let references = match self
+            .message
+            .headers
+            .as_ref()
+            .and_then(|headers| headers.get("References"))
+        {
+            Some(references) => format!("{references} {message_id}"),
+            None => message_id.clone(),
+        };
+
+        self.header("In-Reply-To", message_id)
+            .header("References", references)
+    }
+
+    /// Build the [`SendMessage`].
+    pub fn build(self) -> SendMessage {
+        self.message
+    }
+}
+
+#[cfg(feature = "send")]
 #[derive(Debug, Serialize, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 pub struct Attachment {
@@ -415,13 +1242,46 @@ pub struct Attachment {
     filename: String,
 }
 
+#[cfg(feature = "send")]
 impl Attachment {
     /// Returns [`AttachmentBuilder`] to create an [`Attachment`].
     pub fn builder<'a>() -> AttachmentBuilder<'a> {
         AttachmentBuilder::new()
     }
+
+    /// Filename
+    pub fn filename(&self) -> &str {
+        &self.filename
+    }
+
+    /// Content type, `None` if it is detected by Mailpit
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+
+    /// Content-ID (cid) of an inline attachment
+    pub fn content_id(&self) -> Option<&str> {
+        self.content_id.as_deref()
+    }
+
+    /// Whether the file is attached inline, i.e. has a Content-ID
+    pub fn is_inline(&self) -> bool {
+        self.content_id().is_some_and(|id| !id.is_empty())
+    }
+
+    /// Size of the file content in bytes, before Base64 encoding
+    pub fn content_len(&self) -> usize {
+        let padding = self
+            .content
+            .bytes()
+            .rev()
+            .take_while(|&b| b == b'=')
+            .count();
+        self.content.len() / 4 * 3 - padding
+    }
 }
 
+#[cfg(feature = "send")]
 /// Builder to create an [`Attachment`].
 #[derive(Default)]
 pub struct AttachmentBuilder<'a> {
@@ -431,6 +1291,7 @@ pub struct AttachmentBuilder<'a> {
     filename: Option<&'a str>,
 }
 
+#[cfg(feature = "send")]
 impl<'a> AttachmentBuilder<'a> {
     /// Returns [`AttachmentBuilder`] to create an [`Attachment`].
     pub fn new() -> Self {
@@ -483,6 +1344,7 @@ impl<'a> AttachmentBuilder<'a> {
     }
 }
 
+#[cfg(feature = "send")]
 #[derive(Debug, Deserialize, PartialEq)]
 /// Confirmation message for HTTP send API
 pub struct SendMessageResponse {
@@ -509,11 +1371,13 @@ pub(crate) struct DeleteMessagesFilter<'a> {
     pub(crate) ids: &'a [&'a str],
 }
 
+#[cfg(feature = "checks")]
 #[derive(Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 /// Response represents the HTML check response struct
 pub struct HtmlCheckResponse {
-    /// All platforms tested, mainly for the web UI
+    /// All platforms tested, mainly for the web UI. Maps each platform to
+    /// the email clients tested on it.
     pub platforms: HashMap<String, Vec<String>>,
     /// Total weighted result for all scores
     pub total: HtmlTotalScores,
@@ -521,6 +1385,67 @@ pub struct HtmlCheckResponse {
     pub warnings: Vec<HtmlWarning>,
 }
 
+#[cfg(feature = "checks")]
+impl HtmlCheckResponse {
+    /// Names of all tested platforms, e.g. `android`, `ios` or `webmail`,
+    /// in no particular order.
+    ///
+    /// ```
+    /// # use mailpit_client::models::HtmlCheckResponse;
+    /// let response: HtmlCheckResponse = serde_json::from_str(r#"{
+    ///   "Platforms": { "property1": ["string"], "property2": ["string"] },
+    ///   "Total": { "Nodes": 0, "Partial": 0, "Supported": 0, "Tests": 0, "Unsupported": 0 },
+    ///   "Warnings": []
+    /// }"#)?;
+    ///
+    /// let mut platforms: Vec<_> = response.supported_platforms().collect();
+    /// platforms.sort();
+    /// assert_eq!(vec!["property1", "property2"], platforms);
+    /// # Ok::<(), serde_json::Error>(())
+    /// ```
+    pub fn supported_platforms(&self) -> impl Iterator<Item = &str> {
+        self.platforms.keys().map(String::as_str)
+    }
+
+    /// Email clients that were tested on the given `platform`, e.g.
+    /// `Gmail` or `Outlook` for `webmail`. Returns `None` if the platform
+    /// was not tested.
+    ///
+    /// ```
+    /// # use mailpit_client::models::HtmlCheckResponse;
+    /// let response: HtmlCheckResponse = serde_json::from_str(r#"{
+    ///   "Platforms": { "property1": ["string"], "property2": ["string"] },
+    ///   "Total": { "Nodes": 0, "Partial": 0, "Supported": 0, "Tests": 0, "Unsupported": 0 },
+    ///   "Warnings": []
+    /// }"#)?;
+    ///
+    /// assert_eq!(Some(&["string".to_string()][..]), response.platform_versions("property1"));
+    /// assert_eq!(None, response.platform_versions("unknown"));
+    /// # Ok::<(), serde_json::Error>(())
+    /// ```
+    pub fn platform_versions(&self, platform: &str) -> Option<&[String]> {
+        self.platforms.get(platform).map(Vec::as_slice)
+    }
+
+    /// Warnings whose supported percentage is below `supported_threshold`,
+    /// e.g. `80.0` for all warnings with less than 80% support.
+    pub fn warnings_below(&self, supported_threshold: f32) -> Vec<&HtmlWarning> {
+        self.warnings
+            .iter()
+            .filter(|w| w.score.supported < supported_threshold)
+            .collect()
+    }
+
+    /// Warning with the lowest supported percentage, or `None` if there
+    /// are no warnings.
+    pub fn worst_supported(&self) -> Option<&HtmlWarning> {
+        self.warnings
+            .iter()
+            .min_by(|a, b| a.score.supported.total_cmp(&b.score.supported))
+    }
+}
+
+#[cfg(feature = "checks")]
 #[derive(Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 /// Response represents the Link check response
@@ -531,6 +1456,7 @@ pub struct LinkCheckResponse {
     pub links: Vec<TestedLink>,
 }
 
+#[cfg(feature = "checks")]
 #[derive(Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 /// Tested link
@@ -544,6 +1470,18 @@ pub struct TestedLink {
     pub url: Url,
 }
 
+#[cfg(feature = "checks")]
+impl TestedLink {
+    /// [`TestedLink::status_code`] as [`StatusCode`], `None` if it is not
+    /// a valid HTTP status code, e.g. `0` for an unreachable link.
+    pub fn status_code_typed(&self) -> Option<StatusCode> {
+        u16::try_from(self.status_code)
+            .ok()
+            .and_then(|code| StatusCode::from_u16(code).ok())
+    }
+}
+
+#[cfg(feature = "checks")]
 #[derive(Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 /// Total weighted result for all scores
@@ -560,6 +1498,7 @@ pub struct HtmlTotalScores {
     pub unsupported: f32,
 }
 
+#[cfg(feature = "checks")]
 #[derive(Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 /// List of warnings from tests
@@ -587,6 +1526,21 @@ pub struct HtmlWarning {
     pub url: String,
 }
 
+#[cfg(feature = "checks")]
+impl HtmlWarning {
+    /// The caniemail.com URL of this warning, `None` if [`HtmlWarning::url`]
+    /// is not a valid URL.
+    pub fn caniemail_url(&self) -> Option<Url> {
+        Url::parse(&self.url).ok()
+    }
+
+    /// The caniemail.com feature slug of this warning, e.g. `css-display-flex`.
+    pub fn feature_slug(&self) -> &str {
+        &self.slug
+    }
+}
+
+#[cfg(feature = "checks")]
 #[derive(Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 /// Test results
@@ -605,6 +1559,7 @@ pub struct WarningResult {
     pub version: String,
 }
 
+#[cfg(feature = "checks")]
 #[derive(Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 /// Score struct
@@ -619,6 +1574,7 @@ pub struct WarningScore {
     pub unsupported: f32,
 }
 
+#[cfg(feature = "checks")]
 #[derive(Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 /// Result is a SpamAssassin result
@@ -633,6 +1589,7 @@ pub struct SpamAssassinResponse {
     pub score: f32,
 }
 
+#[cfg(feature = "checks")]
 #[derive(Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 /// Spam rule
@@ -645,9 +1602,11 @@ pub struct SpamRule {
     pub score: f32,
 }
 
+#[cfg(feature = "tags")]
 /// Tag array
 pub type TagList = Vec<String>;
 
+#[cfg(feature = "tags")]
 #[derive(Debug, Serialize, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 pub(crate) struct SetMessageTagsParams<'a> {
@@ -658,6 +1617,7 @@ pub(crate) struct SetMessageTagsParams<'a> {
     pub(crate) tags: &'a [&'a str],
 }
 
+#[cfg(feature = "tags")]
 #[derive(Debug, Serialize, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 pub(crate) struct RenameTagParams<'a> {
@@ -665,6 +1625,7 @@ pub(crate) struct RenameTagParams<'a> {
     pub(crate) name: &'a str,
 }
 
+#[cfg(feature = "chaos")]
 #[derive(Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 /// Triggers for the Chaos configuration
@@ -677,6 +1638,7 @@ pub struct ChaosTriggersResponse {
     pub sender: ChaosTrigger,
 }
 
+#[cfg(feature = "chaos")]
 #[derive(Debug, Deserialize, Serialize, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 /// Trigger for Chaos
@@ -688,6 +1650,34 @@ pub struct ChaosTrigger {
     pub probability: i32,
 }
 
+#[cfg(feature = "chaos")]
+impl ChaosTrigger {
+    /// Create a new [`ChaosTrigger`], validating that `error_code` ranges
+    /// from 400 to 599 and `probability` ranges from 0 to 100.
+    pub fn new(error_code: i32, probability: i32) -> Result<Self, Error> {
+        let trigger = Self {
+            error_code,
+            probability,
+        };
+        trigger.validate()?;
+        Ok(trigger)
+    }
+
+    /// Check that `error_code` ranges from 400 to 599 and `probability`
+    /// ranges from 0 to 100, returning [`Error::InvalidChaosTrigger`]
+    /// otherwise.
+    pub fn validate(&self) -> Result<(), Error> {
+        if !(400..=599).contains(&self.error_code) || !(0..=100).contains(&self.probability) {
+            return Err(Error::InvalidChaosTrigger {
+                error_code: self.error_code,
+                probability: self.probability,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "chaos")]
 #[derive(Debug, Serialize, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 /// Triggers for the Chaos configuration
@@ -699,3 +1689,139 @@ pub struct ChaosTriggersConfiguration {
     /// Trigger for Chaos
     pub sender: ChaosTrigger,
 }
+
+#[cfg(feature = "chaos")]
+impl ChaosTriggersConfiguration {
+    /// Validate all triggers, see [`ChaosTrigger::validate`].
+    pub fn validate(&self) -> Result<(), Error> {
+        self.authentication.validate()?;
+        self.recipient.validate()?;
+        self.sender.validate()
+    }
+}
+
+/// How polling helpers like [`wait_until_empty`] poll Mailpit.
+///
+/// [`wait_until_empty`]: crate::client::MailpitClient::wait_until_empty
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PollConfig {
+    /// Interval between two polls. Defaults to 100 milliseconds.
+    pub interval: Duration,
+    /// Maximum random delay added to every interval, so many clients
+    /// polling at once spread their requests. Defaults to no jitter.
+    pub jitter: Duration,
+    /// Time after which polling gives up with [`Error::Timeout`]. Defaults
+    /// to 10 seconds.
+    pub timeout: Duration,
+}
+
+impl Default for PollConfig {
+    fn default() -> Self {
+        PollConfig {
+            interval: Duration::from_millis(100),
+            jitter: Duration::ZERO,
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl PollConfig {
+    /// Default [`PollConfig`] with the given `timeout`.
+    pub fn with_timeout(timeout: Duration) -> Self {
+        PollConfig {
+            timeout,
+            ..PollConfig::default()
+        }
+    }
+
+    /// Delay until the next poll: the interval plus a random jitter.
+    pub(crate) fn next_delay(&self) -> Duration {
+        let jitter = fastrand::u64(..=self.jitter.as_millis() as u64);
+        self.interval + Duration::from_millis(jitter)
+    }
+}
+
+/// How bulk helpers like [`get_messages`] handle failing items.
+///
+/// [`get_messages`]: crate::client::MailpitClient::get_messages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BulkMode {
+    /// Return the first error, dropping all results. This is the default.
+    #[default]
+    FailFast,
+    /// Process all items and collect failures in [`BulkOutcome::failed`].
+    CollectErrors,
+}
+
+#[derive(Debug)]
+/// Result of a bulk helper
+pub struct BulkOutcome<T> {
+    /// Results of the successful items, in input order
+    pub succeeded: Vec<T>,
+    /// Failed items and their error, in input order. Always empty for
+    /// [`BulkMode::FailFast`].
+    pub failed: Vec<(String, Error)>,
+}
+
+impl<T> BulkOutcome<T> {
+    /// Whether all items succeeded.
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+#[cfg(feature = "checks")]
+#[derive(Debug, PartialEq)]
+/// Summary of a message together with the results of all checks
+pub struct MessageReport {
+    /// Message summary
+    pub summary: MessageSummary,
+    /// HTML check, `None` if disabled at runtime
+    pub html_check: Option<HtmlCheckResponse>,
+    /// Link check, `None` if disabled at runtime
+    pub link_check: Option<LinkCheckResponse>,
+    /// SpamAssassin check, `None` if SpamAssassin is disabled at runtime
+    pub spam_assassin_check: Option<SpamAssassinResponse>,
+}
+
+/// How [`get_render_message_html_part`] renders the HTML part, sent as
+/// `embed` query parameter.
+///
+/// [`get_render_message_html_part`]: crate::client::MailpitClient::get_render_message_html_part
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbedMode {
+    /// Render the HTML as a standalone page, `embed=0`
+    Standalone,
+    /// Render the HTML to be embedded in another page, e.g. an iframe,
+    /// `embed=1`
+    Embedded,
+}
+
+impl EmbedMode {
+    /// Value of the `embed` query parameter.
+    pub(crate) fn query_value(self) -> u8 {
+        match self {
+            EmbedMode::Standalone => 0,
+            EmbedMode::Embedded => 1,
+        }
+    }
+}
+
+impl From<bool> for EmbedMode {
+    fn from(embed: bool) -> Self {
+        if embed {
+            EmbedMode::Embedded
+        } else {
+            EmbedMode::Standalone
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+/// Rendered HTML and text parts of a message
+pub struct RenderedMessage {
+    /// Rendered HTML part, `None` if the message has no HTML part
+    pub html: Option<String>,
+    /// Rendered text part
+    pub text: String,
+}
@@ -0,0 +1,47 @@
+use std::fmt::Display;
+
+use chrono_tz::Tz;
+use reqwest::RequestBuilder;
+
+/// Query parameters of a request, assembled in insertion order.
+///
+/// Every method building a query string goes through this type, so `None`
+/// values are always skipped, present values are always sent (including
+/// `limit=0`) and time zones are always sent by their IANA name.
+#[derive(Debug, Default)]
+pub(crate) struct QueryParams {
+    params: Vec<(&'static str, String)>,
+}
+
+impl QueryParams {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the parameter `key` with `value`.
+    pub(crate) fn param(mut self, key: &'static str, value: impl Display) -> Self {
+        self.params.push((key, value.to_string()));
+        self
+    }
+
+    /// Add the parameter `key` if `value` is set.
+    pub(crate) fn opt(self, key: &'static str, value: Option<impl Display>) -> Self {
+        match value {
+            Some(value) => self.param(key, value),
+            None => self,
+        }
+    }
+
+    /// Add the `tz` parameter with the IANA name of `tz` if it is set.
+    pub(crate) fn tz(self, tz: Option<Tz>) -> Self {
+        self.opt("tz", tz.map(|tz| tz.name()))
+    }
+
+    /// Add the parameters to the query string of `builder`.
+    pub(crate) fn apply(self, builder: RequestBuilder) -> RequestBuilder {
+        if self.params.is_empty() {
+            return builder;
+        }
+        builder.query(&self.params)
+    }
+}
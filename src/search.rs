@@ -0,0 +1,334 @@
+use std::{
+    fmt::{self, Display},
+    time::Duration,
+};
+
+use chrono::{DateTime, SecondsFormat, TimeDelta, TimeZone, Utc};
+
+use crate::error::Error;
+
+/// A single term of a Mailpit [search](https://mailpit.axllent.org/docs/usage/search-filters/).
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchTerm {
+    /// `tag:{value}`
+    Tag(String),
+    /// `from:{value}`
+    From(String),
+    /// `to:{value}`
+    To(String),
+    /// `subject:{value}`
+    Subject(String),
+    /// `is:read` or `is:unread`
+    Read(bool),
+    /// `has:attachment`
+    HasAttachment,
+    /// `after:{date}`
+    After(String),
+    /// `before:{date}`
+    Before(String),
+    /// Free text matched against the whole message
+    Text(String),
+    /// Any filter not modelled by the other variants, kept verbatim
+    Raw(String),
+}
+
+impl SearchTerm {
+    /// Value of this term that gets quoted, if any.
+    fn value(&self) -> Option<&str> {
+        match self {
+            SearchTerm::Tag(v)
+            | SearchTerm::From(v)
+            | SearchTerm::To(v)
+            | SearchTerm::Subject(v)
+            | SearchTerm::After(v)
+            | SearchTerm::Before(v)
+            | SearchTerm::Text(v) => Some(v),
+            SearchTerm::Read(_) | SearchTerm::HasAttachment | SearchTerm::Raw(_) => None,
+        }
+    }
+}
+
+impl Display for SearchTerm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SearchTerm::Tag(v) => write!(f, "tag:{}", quote(v)),
+            SearchTerm::From(v) => write!(f, "from:{}", quote(v)),
+            SearchTerm::To(v) => write!(f, "to:{}", quote(v)),
+            SearchTerm::Subject(v) => write!(f, "subject:{}", quote(v)),
+            SearchTerm::Read(true) => f.write_str("is:read"),
+            SearchTerm::Read(false) => f.write_str("is:unread"),
+            SearchTerm::HasAttachment => f.write_str("has:attachment"),
+            SearchTerm::After(v) => write!(f, "after:{}", quote(v)),
+            SearchTerm::Before(v) => write!(f, "before:{}", quote(v)),
+            SearchTerm::Text(v) => f.write_str(&quote(v)),
+            SearchTerm::Raw(v) => f.write_str(v),
+        }
+    }
+}
+
+/// Structured representation of a Mailpit [search](https://mailpit.axllent.org/docs/usage/search-filters/)
+/// query.
+///
+/// A query can either be built term by term or parsed from an existing
+/// search string. Terms keep their order, so parsing a query and building
+/// it again returns the same search string for all recognized forms.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchQuery {
+    terms: Vec<SearchTerm>,
+}
+
+impl SearchQuery {
+    /// Create an empty [`SearchQuery`].
+    pub fn new() -> Self {
+        SearchQuery::default()
+    }
+
+    /// Parse a Mailpit search string into a [`SearchQuery`].
+    ///
+    /// The `tag:`, `from:`, `to:`, `subject:`, `after:`, `before:`, `is:read`,
+    /// `is:unread` and `has:attachment` filters are recognized, values may be wrapped in
+    /// double quotes. Any other filter and any negated term, i.e. starting
+    /// with `-` or `!`, is kept verbatim as [`SearchTerm::Raw`] and the
+    /// remainder is treated as free text.
+    pub fn parse(s: &str) -> Result<SearchQuery, Error> {
+        let mut terms = Vec::new();
+        for token in tokenize(s)? {
+            terms.push(parse_term(token)?);
+        }
+
+        Ok(SearchQuery { terms })
+    }
+
+    /// Terms of this query, in order.
+    pub fn terms(&self) -> &[SearchTerm] {
+        &self.terms
+    }
+
+    /// Mutable access to the terms of this query.
+    pub fn terms_mut(&mut self) -> &mut Vec<SearchTerm> {
+        &mut self.terms
+    }
+
+    /// Append a [`SearchTerm`].
+    pub fn term(mut self, term: SearchTerm) -> Self {
+        self.terms.push(term);
+        self
+    }
+
+    /// Match messages with the given tag.
+    pub fn tag(self, tag: impl Into<String>) -> Self {
+        self.term(SearchTerm::Tag(tag.into()))
+    }
+
+    /// Match messages from the given sender.
+    pub fn from(self, from: impl Into<String>) -> Self {
+        self.term(SearchTerm::From(from.into()))
+    }
+
+    /// Match messages to the given recipient.
+    pub fn to(self, to: impl Into<String>) -> Self {
+        self.term(SearchTerm::To(to.into()))
+    }
+
+    /// Match messages with the given subject.
+    pub fn subject(self, subject: impl Into<String>) -> Self {
+        self.term(SearchTerm::Subject(subject.into()))
+    }
+
+    /// Match read (`true`) or unread (`false`) messages.
+    pub fn is_read(self, read: bool) -> Self {
+        self.term(SearchTerm::Read(read))
+    }
+
+    /// Match messages with at least one attachment.
+    pub fn has_attachment(self) -> Self {
+        self.term(SearchTerm::HasAttachment)
+    }
+
+    /// Match messages received after `time`.
+    pub fn since(self, time: DateTime<Utc>) -> Self {
+        self.term(SearchTerm::After(
+            time.to_rfc3339_opts(SecondsFormat::Secs, true),
+        ))
+    }
+
+    /// Match messages received within the last `duration`, e.g. the last
+    /// hour. Shorthand for [`SearchQuery::since`] with the current time
+    /// minus `duration`.
+    pub fn within_last(self, duration: Duration) -> Self {
+        self.within_last_at(Utc::now(), duration)
+    }
+
+    /// Same as [`SearchQuery::within_last`], but relative to `now` instead
+    /// of the current time.
+    pub fn within_last_at(self, now: DateTime<Utc>, duration: Duration) -> Self {
+        self.since(cutoff_from(now, duration))
+    }
+
+    /// Match messages received before `time`. The time is sent as RFC 3339
+    /// timestamp with the offset of its time zone, e.g. `Z` for UTC.
+    pub fn before<Z: TimeZone>(self, time: DateTime<Z>) -> Self
+    where
+        Z::Offset: Display,
+    {
+        self.term(SearchTerm::Before(
+            time.to_rfc3339_opts(SecondsFormat::Secs, true),
+        ))
+    }
+
+    /// Match messages received more than `age` ago. Shorthand for
+    /// [`SearchQuery::before`] with the current time minus `age`.
+    pub fn older_than(self, age: Duration) -> Self {
+        self.older_than_at(Utc::now(), age)
+    }
+
+    /// Same as [`SearchQuery::older_than`], but relative to `now` instead of
+    /// the current time.
+    pub fn older_than_at(self, now: DateTime<Utc>, age: Duration) -> Self {
+        self.before(cutoff_from(now, age))
+    }
+
+    /// Match messages containing the given free text.
+    pub fn text(self, text: impl Into<String>) -> Self {
+        self.term(SearchTerm::Text(text.into()))
+    }
+
+    /// Build the search string to pass to Mailpit.
+    ///
+    /// Values containing a double quote can not be quoted and are sent as
+    /// is, see [`SearchQuery::try_build`] to reject them instead.
+    pub fn build(&self) -> String {
+        self.to_string()
+    }
+
+    /// Build the search string to pass to Mailpit, returning
+    /// [`Error::InvalidSearchQuery`] if a value contains a double quote.
+    /// Mailpit search has no way to escape them, so such a value would end
+    /// the quoted value early and change the meaning of the query.
+    pub fn try_build(&self) -> Result<String, Error> {
+        if let Some(value) = self
+            .terms
+            .iter()
+            .filter_map(SearchTerm::value)
+            .find(|value| value.contains('"'))
+        {
+            return Err(Error::InvalidSearchQuery(format!(
+                "double quote in value `{value}`"
+            )));
+        }
+        Ok(self.build())
+    }
+}
+
+impl Display for SearchQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, term) in self.terms.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{term}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The current time minus `duration`, saturating at the earliest
+/// representable time.
+pub(crate) fn cutoff(duration: Duration) -> DateTime<Utc> {
+    cutoff_from(Utc::now(), duration)
+}
+
+/// `now` minus `duration`, saturating at the earliest representable time.
+pub(crate) fn cutoff_from(now: DateTime<Utc>, duration: Duration) -> DateTime<Utc> {
+    TimeDelta::from_std(duration)
+        .ok()
+        .and_then(|duration| now.checked_sub_signed(duration))
+        .unwrap_or(DateTime::<Utc>::MIN_UTC)
+}
+
+/// Wrap `value` in double quotes if it would otherwise be split into
+/// multiple terms or, starting with `-` or `!`, be read as a negation.
+fn quote(value: &str) -> String {
+    if value.is_empty() || value.contains(char::is_whitespace) || value.starts_with(['-', '!']) {
+        format!("\"{value}\"")
+    } else {
+        value.to_string()
+    }
+}
+
+/// Strip the surrounding double quotes of `value`, if any.
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+/// Split a search string on whitespace outside of double quotes.
+fn tokenize(s: &str) -> Result<Vec<&str>, Error> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    let mut quoted = false;
+
+    for (i, c) in s.char_indices() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                start.get_or_insert(i);
+            }
+            c if c.is_whitespace() && !quoted => {
+                if let Some(start) = start.take() {
+                    tokens.push(&s[start..i]);
+                }
+            }
+            _ => {
+                start.get_or_insert(i);
+            }
+        }
+    }
+
+    if quoted {
+        return Err(Error::InvalidSearchQuery(format!(
+            "unterminated quote in `{s}`"
+        )));
+    }
+    if let Some(start) = start {
+        tokens.push(&s[start..]);
+    }
+
+    Ok(tokens)
+}
+
+fn parse_term(token: &str) -> Result<SearchTerm, Error> {
+    let term = match token {
+        "is:read" => SearchTerm::Read(true),
+        "is:unread" => SearchTerm::Read(false),
+        "has:attachment" => SearchTerm::HasAttachment,
+        _ => {
+            if token.starts_with(['-', '!']) {
+                return Ok(SearchTerm::Raw(token.to_string()));
+            }
+            let Some((filter, value)) = token.split_once(':') else {
+                return Ok(SearchTerm::Text(unquote(token).to_string()));
+            };
+            let value = unquote(value);
+            let term = match filter {
+                "tag" => SearchTerm::Tag,
+                "from" => SearchTerm::From,
+                "to" => SearchTerm::To,
+                "subject" => SearchTerm::Subject,
+                "after" => SearchTerm::After,
+                "before" => SearchTerm::Before,
+                _ => return Ok(SearchTerm::Raw(token.to_string())),
+            };
+            if value.is_empty() {
+                return Err(Error::InvalidSearchQuery(format!(
+                    "missing value for `{filter}:`"
+                )));
+            }
+            term(value.to_string())
+        }
+    };
+
+    Ok(term)
+}
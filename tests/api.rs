@@ -0,0 +1,215 @@
+use bytes::Bytes;
+use httpmock::{Method::GET, MockServer};
+use mailpit_client::{
+    MailpitApi, MailpitClient, Tz,
+    error::Error,
+    models::{
+        ApplicationInformation, ChaosTriggersConfiguration, ChaosTriggersResponse, EmbedMode,
+        HtmlCheckResponse, LinkCheckResponse, MessageHeaders, MessageSummary, MessagesSummary,
+        SendMessage, SendMessageResponse, SpamAssassinResponse, TagList, WebUIConfiguration,
+    },
+};
+use pretty_assertions::assert_str_eq;
+
+/// Business logic under test, depending on the trait only.
+async fn latest_subject(api: &impl MailpitApi) -> Result<String, Error> {
+    let message = api.get_message_summary("latest").await?;
+    Ok(message.subject().to_string())
+}
+
+fn message_summary(subject: &str) -> String {
+    format!(
+        r#"{{
+          "Attachments": [],
+          "Bcc": [],
+          "Cc": [],
+          "Date": "1970-01-01T00:00:00.000Z",
+          "From": {{ "Address": "jane@example.com", "Name": "Jane Doe" }},
+          "HTML": "",
+          "ID": "database-id",
+          "Inline": [],
+          "ListUnsubscribe": {{ "Errors": "", "Header": "", "HeaderPost": "", "Links": [] }},
+          "MessageID": "message-id",
+          "ReplyTo": [],
+          "ReturnPath": "",
+          "Size": 0,
+          "Subject": "{subject}",
+          "Tags": [],
+          "Text": "",
+          "To": [],
+          "Username": ""
+        }}"#
+    )
+}
+
+/// Fake only implementing the endpoints used by [`latest_subject`].
+struct FakeMailpit {
+    subject: &'static str,
+}
+
+impl MailpitApi for FakeMailpit {
+    async fn get_application_information(&self) -> Result<ApplicationInformation, Error> {
+        unimplemented!()
+    }
+
+    async fn get_webui_configuration(&self) -> Result<WebUIConfiguration, Error> {
+        unimplemented!()
+    }
+
+    async fn get_message_summary(&self, _id: &str) -> Result<MessageSummary, Error> {
+        Ok(serde_json::from_str(&message_summary(self.subject))?)
+    }
+
+    async fn get_message_headers(&self, _id: &str) -> Result<MessageHeaders, Error> {
+        unimplemented!()
+    }
+
+    async fn get_message_attachment(&self, _id: &str, _part_id: &str) -> Result<Bytes, Error> {
+        unimplemented!()
+    }
+
+    async fn get_message_attachment_image_thumbnail(
+        &self,
+        _id: &str,
+        _part_id: &str,
+    ) -> Result<Bytes, Error> {
+        unimplemented!()
+    }
+
+    async fn get_message_source(&self, _id: &str) -> Result<String, Error> {
+        unimplemented!()
+    }
+
+    async fn post_release_message(&self, _id: &str, _to: &[&str]) -> Result<bool, Error> {
+        unimplemented!()
+    }
+
+    async fn post_send_message(&self, _message: SendMessage) -> Result<SendMessageResponse, Error> {
+        unimplemented!()
+    }
+
+    async fn get_list_messages(
+        &self,
+        _start: Option<usize>,
+        _limit: Option<usize>,
+    ) -> Result<MessagesSummary, Error> {
+        unimplemented!()
+    }
+
+    async fn put_set_read_status(
+        &self,
+        _read: Option<bool>,
+        _ids: Option<&[&str]>,
+        _search: Option<&str>,
+        _tz: Option<Tz>,
+    ) -> Result<bool, Error> {
+        unimplemented!()
+    }
+
+    async fn delete_messages(&self, _message_ids: &[&str]) -> Result<bool, Error> {
+        unimplemented!()
+    }
+
+    async fn get_search_messages(
+        &self,
+        _query: &str,
+        _start: Option<usize>,
+        _limit: Option<usize>,
+        _tz: Option<Tz>,
+    ) -> Result<MessagesSummary, Error> {
+        unimplemented!()
+    }
+
+    async fn delete_messages_by_search(
+        &self,
+        _query: &str,
+        _tz: Option<Tz>,
+    ) -> Result<bool, Error> {
+        unimplemented!()
+    }
+
+    async fn get_html_check(
+        &self,
+        _id: &str,
+        _locale: Option<&str>,
+    ) -> Result<HtmlCheckResponse, Error> {
+        unimplemented!()
+    }
+
+    async fn get_link_check(
+        &self,
+        _id: &str,
+        _follow: Option<bool>,
+    ) -> Result<LinkCheckResponse, Error> {
+        unimplemented!()
+    }
+
+    async fn get_spam_assassin_check(&self, _id: &str) -> Result<SpamAssassinResponse, Error> {
+        unimplemented!()
+    }
+
+    async fn get_all_current_tags(&self) -> Result<TagList, Error> {
+        unimplemented!()
+    }
+
+    async fn put_set_message_tags(&self, _ids: &[&str], _tags: &[&str]) -> Result<bool, Error> {
+        unimplemented!()
+    }
+
+    async fn put_rename_tag(&self, _tag: &str, _name: &str) -> Result<bool, Error> {
+        unimplemented!()
+    }
+
+    async fn delete_tag(&self, _tag: &str) -> Result<bool, Error> {
+        unimplemented!()
+    }
+
+    async fn get_chaos_triggers(&self) -> Result<ChaosTriggersResponse, Error> {
+        unimplemented!()
+    }
+
+    async fn put_set_chaos_triggers(
+        &self,
+        _config: Option<ChaosTriggersConfiguration>,
+    ) -> Result<ChaosTriggersResponse, Error> {
+        unimplemented!()
+    }
+
+    async fn get_render_message_html_part(
+        &self,
+        _id: &str,
+        _embed: Option<EmbedMode>,
+    ) -> Result<String, Error> {
+        unimplemented!()
+    }
+
+    async fn get_render_message_text_part(&self, _id: &str) -> Result<String, Error> {
+        unimplemented!()
+    }
+}
+
+#[tokio::test]
+async fn mailpit_api_fake_success() {
+    let fake = FakeMailpit { subject: "Fake" };
+
+    assert_str_eq!("Fake", latest_subject(&fake).await.unwrap());
+}
+
+#[tokio::test]
+async fn mailpit_api_client_success() {
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/message/latest");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(message_summary("Real"));
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+
+    assert_str_eq!("Real", latest_subject(&client).await.unwrap());
+
+    mock.assert();
+}
@@ -1,7 +1,9 @@
+use std::{collections::HashMap, time::Duration};
+
 use httpmock::{Method::GET, MockServer};
 use mailpit_client::{
     MailpitClient,
-    models::{ApplicationInformation, WebUIConfiguration},
+    models::{ApplicationInformation, InfoDelta, MailboxStats, RuntimeStats, WebUIConfiguration},
 };
 use pretty_assertions::assert_eq;
 
@@ -86,3 +88,296 @@ async fn get_web_ui_configuration_success() {
 
     mock.assert();
 }
+
+#[tokio::test]
+async fn get_application_information_with_subpath_success() {
+    let expected_response = r#"{
+      "Database": "string",
+      "DatabaseSize": 0,
+      "LatestVersion": "string",
+      "Messages": 0,
+      "RuntimeStats": {
+        "Memory": 0,
+        "MessagesDeleted": 0,
+        "SMTPAccepted": 0,
+        "SMTPAcceptedSize": 0,
+        "SMTPIgnored": 0,
+        "SMTPRejected": 0,
+        "Uptime": 0
+      },
+      "Tags": {},
+      "Unread": 0,
+      "Version": "string"
+    }"#;
+
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/mail/api/v1/info");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(expected_response);
+        })
+        .await;
+
+    for base_url in [server.url("/mail"), server.url("/mail/")] {
+        let client = MailpitClient::new(&base_url).unwrap();
+        let response = client.get_application_information().await.unwrap();
+
+        let expected_response: ApplicationInformation =
+            serde_json::from_str(expected_response).unwrap();
+        assert_eq!(&expected_response, &response);
+    }
+
+    mock.assert_calls(2);
+}
+
+#[tokio::test]
+async fn get_instance_label_success() {
+    let expected_response = r#"{
+      "ChaosEnabled": false,
+      "DuplicatesIgnored": false,
+      "HideDeleteAllButton": false,
+      "Label": "staging",
+      "MessageRelay": {
+        "AllowedRecipients": "",
+        "BlockedRecipients": "",
+        "Enabled": false,
+        "OverrideFrom": "",
+        "PreserveMessageIDs": false,
+        "ReturnPath": "",
+        "SMTPServer": ""
+      },
+      "SpamAssassin": false
+    }"#;
+
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/webui");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(expected_response);
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let label = client.get_instance_label().await.unwrap();
+
+    assert_eq!("staging", label);
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn stats_success() {
+    let info_response = r#"{
+      "Database": "/data/mailpit.db",
+      "DatabaseSize": 4096,
+      "LatestVersion": "v1.27.10",
+      "Messages": 3,
+      "RuntimeStats": {
+        "Memory": 1024,
+        "MessagesDeleted": 0,
+        "SMTPAccepted": 3,
+        "SMTPAcceptedSize": 300,
+        "SMTPIgnored": 0,
+        "SMTPRejected": 0,
+        "Uptime": 120
+      },
+      "Tags": {
+        "Tag 1": 2
+      },
+      "Unread": 2,
+      "Version": "v1.27.10"
+    }"#;
+    let messages_response = r#"{
+      "messages": [],
+      "messages_count": 5,
+      "messages_unread": 4,
+      "start": 0,
+      "tags": ["Tag 1"],
+      "total": 5,
+      "unread": 4
+    }"#;
+
+    let server = MockServer::start_async().await;
+    let info_mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/info");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(info_response);
+        })
+        .await;
+    let messages_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/messages")
+                .query_param("limit", "0");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_response);
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let stats = client.stats().await.unwrap();
+
+    assert_eq!(
+        MailboxStats {
+            total: 5,
+            unread: 4,
+            tags: HashMap::from([("Tag 1".to_string(), 2)]),
+            database_size: 4096,
+            memory: 1024,
+            uptime: Duration::from_secs(120),
+            version: "v1.27.10".to_string(),
+        },
+        stats
+    );
+
+    info_mock.assert();
+    messages_mock.assert();
+}
+
+#[tokio::test]
+async fn duplicates_ignored_success() {
+    let expected_response = r#"{
+      "ChaosEnabled": false,
+      "DuplicatesIgnored": true,
+      "HideDeleteAllButton": false,
+      "Label": "",
+      "MessageRelay": {
+        "AllowedRecipients": "",
+        "BlockedRecipients": "",
+        "Enabled": false,
+        "OverrideFrom": "",
+        "PreserveMessageIDs": false,
+        "ReturnPath": "",
+        "SMTPServer": ""
+      },
+      "SpamAssassin": false
+    }"#;
+
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/webui");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(expected_response);
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+
+    assert!(client.duplicates_ignored().await.unwrap());
+
+    mock.assert();
+}
+
+fn runtime_stats(accepted: usize, rejected: usize, uptime: usize) -> RuntimeStats {
+    RuntimeStats {
+        memory: 0,
+        messages_deleted: 1,
+        smtp_accepted: accepted,
+        smtp_accepted_size: accepted * 100,
+        smtp_ignored: 0,
+        smtp_rejected: rejected,
+        uptime,
+    }
+}
+
+#[test]
+fn runtime_stats_delta_success() {
+    let previous = runtime_stats(5, 2, 60);
+    let current = runtime_stats(8, 1, 120);
+
+    assert_eq!(
+        InfoDelta {
+            messages: None,
+            unread: None,
+            messages_deleted: Some(0),
+            smtp_accepted: Some(3),
+            smtp_accepted_size: Some(300),
+            smtp_ignored: Some(0),
+            smtp_rejected: None,
+        },
+        current.delta(&previous)
+    );
+}
+
+#[test]
+fn runtime_stats_delta_restart_success() {
+    let previous = runtime_stats(5, 2, 600);
+    let current = runtime_stats(8, 3, 30);
+
+    assert_eq!(
+        InfoDelta {
+            messages: None,
+            unread: None,
+            messages_deleted: None,
+            smtp_accepted: None,
+            smtp_accepted_size: None,
+            smtp_ignored: None,
+            smtp_rejected: None,
+        },
+        current.delta(&previous)
+    );
+}
+
+fn application_information(
+    messages: usize,
+    unread: usize,
+    stats: RuntimeStats,
+) -> ApplicationInformation {
+    ApplicationInformation {
+        database: String::new(),
+        database_size: 0,
+        latest_version: String::new(),
+        messages,
+        runtime_stats: stats,
+        tags: HashMap::new(),
+        unread,
+        version: String::new(),
+    }
+}
+
+#[test]
+fn application_information_delta_success() {
+    let previous = application_information(10, 4, runtime_stats(5, 2, 60));
+    let current = application_information(13, 6, runtime_stats(8, 2, 120));
+
+    assert_eq!(
+        InfoDelta {
+            messages: Some(3),
+            unread: Some(2),
+            messages_deleted: Some(0),
+            smtp_accepted: Some(3),
+            smtp_accepted_size: Some(300),
+            smtp_ignored: Some(0),
+            smtp_rejected: Some(0),
+        },
+        current.delta(&previous)
+    );
+}
+
+#[test]
+fn application_information_delta_reset_success() {
+    let previous = application_information(10, 4, runtime_stats(5, 2, 600));
+    let current = application_information(2, 1, runtime_stats(1, 0, 30));
+
+    assert_eq!(
+        InfoDelta {
+            messages: None,
+            unread: None,
+            messages_deleted: None,
+            smtp_accepted: None,
+            smtp_accepted_size: None,
+            smtp_ignored: None,
+            smtp_rejected: None,
+        },
+        current.delta(&previous)
+    );
+}
@@ -0,0 +1,489 @@
+use std::time::{Duration, Instant};
+
+use httpmock::{Method::GET, MockServer};
+use mailpit_client::{
+    MailpitClient,
+    error::{Error, MailpitError},
+    models::PollConfig,
+};
+use pretty_assertions::assert_eq;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+
+#[tokio::test]
+async fn builder_with_pool_settings_success() {
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/tags");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(r#"["Tag 1"]"#);
+        })
+        .await;
+
+    let client = MailpitClient::builder(&server.base_url())
+        .pool_max_idle_per_host(4)
+        .pool_idle_timeout(Duration::from_secs(10))
+        .build()
+        .unwrap();
+    let response = client.get_all_current_tags().await.unwrap();
+
+    assert_eq!(vec!["Tag 1".to_string()], response);
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn builder_with_tcp_keepalive_success() {
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/tags");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(r#"["Tag 1"]"#);
+        })
+        .await;
+
+    let client = MailpitClient::builder(&server.base_url())
+        .tcp_keepalive(Duration::from_secs(30))
+        .http2_prior_knowledge(false)
+        .build()
+        .unwrap();
+    let response = client.get_all_current_tags().await.unwrap();
+
+    assert_eq!(vec!["Tag 1".to_string()], response);
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn builder_with_headers_success() {
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/tags")
+                .header("authorization", "Basic dXNlcjpzZWNyZXQ=")
+                .header("user-agent", "mailpit-tests/1.0")
+                .header("x-proxy-token", "token");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(r#"["Tag 1"]"#);
+        })
+        .await;
+
+    let client = MailpitClient::builder(&server.base_url())
+        .basic_auth("user", "secret")
+        .user_agent("mailpit-tests/1.0")
+        .default_header(
+            HeaderName::from_static("x-proxy-token"),
+            HeaderValue::from_static("token"),
+        )
+        .timeout(Duration::from_secs(10))
+        .build()
+        .unwrap();
+    let response = client.get_all_current_tags().await.unwrap();
+
+    assert_eq!(vec!["Tag 1".to_string()], response);
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn new_with_auth_success() {
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/tags")
+                .header("authorization", "Basic dXNlcjpzZWNyZXQ=");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(r#"["Tag 1"]"#);
+        })
+        .await;
+
+    let client = MailpitClient::new_with_auth(&server.base_url(), "user", "secret").unwrap();
+    let response = client.get_all_current_tags().await.unwrap();
+
+    assert_eq!(vec!["Tag 1".to_string()], response);
+
+    mock.assert();
+}
+
+#[test]
+fn builder_invalid_user_agent_failure() {
+    let result = MailpitClient::builder("http://localhost:8025")
+        .user_agent("line\nbreak")
+        .build();
+
+    assert!(matches!(result, Err(Error::ReqwestFailure(_))));
+}
+
+#[tokio::test]
+async fn new_with_timeout_failure() {
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/tags");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(r#"["Tag 1"]"#)
+                .delay(Duration::from_secs(2));
+        })
+        .await;
+
+    let client =
+        MailpitClient::new_with_timeout(&server.base_url(), Duration::from_millis(100)).unwrap();
+    let result = client.get_all_current_tags().await;
+
+    assert!(matches!(result, Err(Error::ReqwestFailure(e)) if e.is_timeout()));
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn builder_with_connect_timeout_failure() {
+    // Non-routable address, connecting never completes.
+    let client = MailpitClient::builder("http://10.255.255.1:8025")
+        .connect_timeout(Duration::from_millis(200))
+        .build()
+        .unwrap();
+
+    let started = Instant::now();
+    let result = client.get_all_current_tags().await;
+
+    assert!(matches!(result, Err(Error::ReqwestFailure(_))));
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn builder_with_default_page_limit_success() {
+    let messages_response = r#"{
+      "messages": [],
+      "messages_count": 0,
+      "messages_unread": 0,
+      "start": 0,
+      "tags": [],
+      "total": 0,
+      "unread": 0
+    }"#;
+
+    let server = MockServer::start_async().await;
+    let list_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/messages")
+                .query_param("limit", "500");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_response);
+        })
+        .await;
+    let search_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/search")
+                .query_param("query", "is:unread")
+                .query_param("limit", "500");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_response);
+        })
+        .await;
+    let explicit_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/messages")
+                .query_param("limit", "0");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_response);
+        })
+        .await;
+
+    let client = MailpitClient::builder(&server.base_url())
+        .default_page_limit(500)
+        .build()
+        .unwrap();
+    client.get_list_messages(None, None).await.unwrap();
+    client
+        .get_search_messages("is:unread", None, None, None)
+        .await
+        .unwrap();
+    client.get_list_messages(None, Some(0)).await.unwrap();
+
+    list_mock.assert();
+    search_mock.assert();
+    explicit_mock.assert();
+}
+
+#[tokio::test]
+async fn wait_ready_success() {
+    let server = MockServer::start_async().await;
+    let booting_mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/readyz");
+            then.status(503).body("not ready");
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let poll = PollConfig {
+        interval: Duration::from_millis(10),
+        ..PollConfig::with_timeout(Duration::from_secs(5))
+    };
+    let (result, ready_mock) = tokio::join!(client.wait_ready(poll), async {
+        while booting_mock.calls_async().await < 2 {
+            tokio::task::yield_now().await;
+        }
+        booting_mock.delete_async().await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/readyz");
+                then.status(200);
+            })
+            .await
+    });
+
+    result.unwrap();
+    ready_mock.assert();
+}
+
+#[tokio::test]
+async fn wait_ready_connection_refused_timeout_failure() {
+    // Bind and drop a listener to get a port nothing listens on.
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+
+    let client = MailpitClient::new(&format!("http://127.0.0.1:{port}")).unwrap();
+    let result = client
+        .wait_ready(PollConfig::with_timeout(Duration::from_millis(250)))
+        .await;
+
+    assert!(matches!(result, Err(Error::Timeout(_))));
+}
+
+#[tokio::test]
+async fn wait_ready_unauthorized_failure() {
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/readyz");
+            then.status(401).body("unauthorized");
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let result = client.wait_ready(PollConfig::default()).await;
+
+    assert!(matches!(
+        result,
+        Err(Error::HttpFailure { status: 401, .. })
+    ));
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn with_client_success() {
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/tags")
+                .header("x-proxy-token", "token");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(r#"["Tag 1"]"#);
+        })
+        .await;
+
+    let headers = HeaderMap::from_iter([(
+        HeaderName::from_static("x-proxy-token"),
+        HeaderValue::from_static("token"),
+    )]);
+    let http = reqwest::Client::builder()
+        .default_headers(headers)
+        .build()
+        .unwrap();
+    let client = MailpitClient::with_client(&server.base_url(), http).unwrap();
+    let response = client.get_all_current_tags().await.unwrap();
+
+    assert_eq!(vec!["Tag 1".to_string()], response);
+
+    mock.assert();
+}
+
+#[test]
+fn with_client_invalid_url_failure() {
+    let result = MailpitClient::with_client("not a url", reqwest::Client::new());
+
+    assert!(matches!(result, Err(Error::InvalidUrl(_))));
+}
+
+#[test]
+fn builder_invalid_url_failure() {
+    let result = MailpitClient::builder("not a url").build();
+
+    assert!(matches!(result, Err(Error::InvalidUrl(_))));
+}
+
+#[test]
+fn base_url_success() {
+    let client = MailpitClient::new("http://localhost:8025/mail").unwrap();
+
+    assert_eq!("http://localhost:8025/mail/", client.base_url().as_str());
+    assert_eq!(client.base_url(), client.as_ref());
+}
+
+#[test]
+fn web_ui_message_url_success() {
+    let client = MailpitClient::new("http://localhost:8025/mail/").unwrap();
+
+    assert_eq!(
+        "http://localhost:8025/mail/view/database-id",
+        client.web_ui_message_url("database-id").as_str()
+    );
+    assert_eq!(
+        "http://localhost:8025/mail/view/latest",
+        client.web_ui_message_url("latest").as_str()
+    );
+}
+
+#[tokio::test]
+async fn connect_success() {
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/info");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(
+                    r#"{
+                      "Database": "string",
+                      "DatabaseSize": 0,
+                      "LatestVersion": "string",
+                      "Messages": 0,
+                      "RuntimeStats": {
+                        "Memory": 0,
+                        "MessagesDeleted": 0,
+                        "SMTPAccepted": 0,
+                        "SMTPAcceptedSize": 0,
+                        "SMTPIgnored": 0,
+                        "SMTPRejected": 0,
+                        "Uptime": 0
+                      },
+                      "Tags": {},
+                      "Unread": 0,
+                      "Version": "string"
+                    }"#,
+                );
+        })
+        .await;
+
+    let client = MailpitClient::connect(&server.base_url()).await.unwrap();
+
+    assert_eq!(server.base_url() + "/", client.base_url().as_str());
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn connect_failure() {
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/info");
+            then.status(404).body("Not found");
+        })
+        .await;
+
+    let result = MailpitClient::connect(&server.base_url()).await;
+
+    assert!(matches!(
+        result,
+        Err(Error::HttpFailure { status: 404, .. })
+    ));
+
+    mock.assert();
+}
+
+#[test]
+fn error_from_io_and_json_success() {
+    let error: Error = std::io::Error::other("disk full").into();
+    assert!(matches!(error, Error::Io(_)));
+    assert_eq!("IO error: disk full", error.to_string());
+
+    let error: Error = serde_json::from_str::<u32>("nope").unwrap_err().into();
+    assert!(matches!(error, Error::Json(_)));
+}
+
+#[tokio::test]
+async fn error_handle_response_success() {
+    let server = MockServer::start_async().await;
+    let tags_mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/tags");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(r#"["Tag 1"]"#);
+        })
+        .await;
+    let missing_mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/unknown");
+            then.status(404).body("not found");
+        })
+        .await;
+
+    let response = reqwest::get(server.url("/api/v1/tags")).await.unwrap();
+    let tags: Vec<String> = Error::handle_response(response).await.unwrap();
+    assert_eq!(vec!["Tag 1".to_string()], tags);
+
+    let response = reqwest::get(server.url("/api/v1/unknown")).await.unwrap();
+    let result = Error::handle_response::<Vec<String>>(response).await;
+    assert!(matches!(
+        result,
+        Err(Error::HttpFailure { status: 404, .. })
+    ));
+
+    tags_mock.assert();
+    missing_mock.assert();
+}
+
+#[tokio::test]
+async fn mailpit_error_round_trip_success() {
+    let error = MailpitError::new("invalid search");
+    let body = serde_json::to_string(&error).unwrap();
+    assert_eq!(r#"{"Error":"invalid search"}"#, body);
+
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/search");
+            then.status(400)
+                .header("content-type", "application/json")
+                .body(&body);
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let result = client.get_search_messages("is:", None, None, None).await;
+
+    let Err(Error::HttpFailure {
+        status: 400,
+        body: Some(body),
+        ..
+    }) = result
+    else {
+        panic!("expected an HTTP failure with a body, got {result:?}");
+    };
+    assert_eq!(error, body);
+
+    mock.assert();
+}
@@ -0,0 +1,311 @@
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt, TryStreamExt};
+use httpmock::{
+    Method::{GET, PUT},
+    MockServer,
+};
+use mailpit_client::{
+    MailpitClient,
+    models::{EventMessage, MessageSummary},
+};
+use pretty_assertions::assert_eq;
+use tokio::{
+    io::copy_bidirectional,
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+    time::timeout,
+};
+use tokio_tungstenite::tungstenite::{
+    Message,
+    handshake::server::{Request, Response},
+    http::header::AUTHORIZATION,
+};
+
+/// Serve a single WebSocket connection sending `events` and closing it
+/// afterwards. Resolves to the path and `Authorization` header of the
+/// upgrade request.
+// The handshake callback signature is given by tungstenite.
+#[allow(clippy::result_large_err)]
+async fn serve_events(events: Vec<&'static str>) -> (String, JoinHandle<(String, Option<String>)>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    let handle = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut path = String::new();
+        let mut authorization = None;
+        let mut socket =
+            tokio_tungstenite::accept_hdr_async(stream, |request: &Request, response: Response| {
+                path = request.uri().path().to_string();
+                authorization = request
+                    .headers()
+                    .get(AUTHORIZATION)
+                    .map(|value| value.to_str().unwrap().to_string());
+                Ok(response)
+            })
+            .await
+            .unwrap();
+
+        for event in events {
+            socket.send(Message::text(event)).await.unwrap();
+        }
+        socket.close(None).await.unwrap();
+        (path, authorization)
+    });
+
+    (url, handle)
+}
+
+#[tokio::test]
+async fn subscribe_events_success() {
+    let (url, server) = serve_events(vec![
+        r#"{
+          "Type": "new",
+          "Data": {
+            "Attachments": 0,
+            "Created": "1970-01-01T00:00:00.000Z",
+            "From": { "Address": "john@example.com", "Name": "John Doe" },
+            "ID": "database-id",
+            "MessageID": "message-id",
+            "Read": false,
+            "ReplyTo": [],
+            "Size": 0,
+            "Snippet": "string",
+            "Subject": "string",
+            "Tags": [],
+            "To": [],
+            "Username": ""
+          }
+        }"#,
+        r#"{ "Type": "update", "Data": { "ID": "database-id", "Read": true } }"#,
+        r#"{ "Type": "delete", "Data": { "ID": "database-id" } }"#,
+        r#"{ "Type": "delete", "Data": { "IDs": ["id-1", "id-2"] } }"#,
+        r#"{ "Type": "truncate", "Data": null }"#,
+        r#"{ "Type": "stats", "Data": { "Total": 0, "Unread": 0 } }"#,
+    ])
+    .await;
+
+    let client = MailpitClient::new(&url).unwrap();
+    let events: Vec<EventMessage> = client
+        .subscribe_events()
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+
+    let EventMessage::NewMessage(message) = &events[0] else {
+        panic!("expected a new message event, got {:?}", events[0]);
+    };
+    assert_eq!("database-id", message.id());
+    assert_eq!(
+        EventMessage::Update(serde_json::json!({ "ID": "database-id", "Read": true })),
+        events[1]
+    );
+    assert_eq!(
+        EventMessage::DeleteMessages(vec!["database-id".to_string()]),
+        events[2]
+    );
+    assert_eq!(
+        EventMessage::DeleteMessages(vec!["id-1".to_string(), "id-2".to_string()]),
+        events[3]
+    );
+    assert_eq!(EventMessage::TruncateMessages, events[4]);
+    assert_eq!(
+        EventMessage::Other {
+            kind: "stats".to_string(),
+            data: serde_json::json!({ "Total": 0, "Unread": 0 }),
+        },
+        events[5]
+    );
+    assert_eq!(6, events.len());
+
+    let (path, authorization) = server.await.unwrap();
+    assert_eq!("/api/events", path);
+    assert_eq!(None, authorization);
+}
+
+#[tokio::test]
+async fn subscribe_events_drop_closes_connection_success() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+        socket
+            .send(Message::text(r#"{ "Type": "truncate", "Data": null }"#))
+            .await
+            .unwrap();
+        // Keep the connection open until the client goes away.
+        while let Some(Ok(_)) = socket.next().await {}
+    });
+
+    let client = MailpitClient::new(&url).unwrap();
+    let mut events = Box::pin(client.subscribe_events().await.unwrap());
+    assert_eq!(
+        EventMessage::TruncateMessages,
+        events.try_next().await.unwrap().unwrap()
+    );
+    drop(events);
+
+    timeout(Duration::from_secs(5), server)
+        .await
+        .expect("connection still open after dropping the stream")
+        .unwrap();
+}
+
+/// Serve a single WebSocket connection sending `events` like
+/// [`serve_events`], forwarding all other connections to `http`.
+async fn serve_events_with_http(events: Vec<String>, http: &MockServer) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let http = *http.address();
+
+    tokio::spawn(async move {
+        let mut events = Some(events);
+        loop {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut start = [0; 16];
+            stream.peek(&mut start).await.unwrap();
+            if start.starts_with(b"GET /api/events") {
+                let events = events.take().unwrap();
+                tokio::spawn(async move {
+                    let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+                    for event in events {
+                        socket.send(Message::text(event)).await.unwrap();
+                    }
+                    socket.close(None).await.unwrap();
+                });
+            } else {
+                tokio::spawn(async move {
+                    let mut upstream = TcpStream::connect(http).await.unwrap();
+                    let _ = copy_bidirectional(&mut stream, &mut upstream).await;
+                });
+            }
+        }
+    });
+
+    url
+}
+
+fn new_message_event(id: &str, read: bool) -> String {
+    format!(
+        r#"{{
+          "Type": "new",
+          "Data": {{
+            "Attachments": 0,
+            "Created": "1970-01-01T00:00:00.000Z",
+            "From": {{ "Address": "john@example.com", "Name": "John Doe" }},
+            "ID": "{id}",
+            "MessageID": "{id}@example.com",
+            "Read": {read},
+            "ReplyTo": [],
+            "Size": 0,
+            "Snippet": "string",
+            "Subject": "string",
+            "Tags": [],
+            "To": [],
+            "Username": ""
+          }}
+        }}"#
+    )
+}
+
+fn message_summary(id: &str) -> String {
+    format!(
+        r#"{{
+          "Attachments": [],
+          "Bcc": [],
+          "Cc": [],
+          "Date": "1970-01-01T00:00:00.000Z",
+          "From": {{ "Address": "john@example.com", "Name": "John Doe" }},
+          "HTML": "",
+          "ID": "{id}",
+          "Inline": [],
+          "ListUnsubscribe": {{ "Errors": "", "Header": "", "HeaderPost": "", "Links": [] }},
+          "MessageID": "{id}@example.com",
+          "ReplyTo": [],
+          "ReturnPath": "",
+          "Size": 0,
+          "Subject": "string",
+          "Tags": [],
+          "Text": "",
+          "To": [],
+          "Username": ""
+        }}"#
+    )
+}
+
+#[tokio::test]
+async fn watch_latest_success() {
+    let http = MockServer::start_async().await;
+    let mut summary_mocks = Vec::new();
+    for id in ["id-1", "id-2"] {
+        let mock = http
+            .mock_async(|when, then| {
+                when.method(GET).path(format!("/api/v1/message/{id}"));
+                then.status(200)
+                    .header("content-type", "application/json")
+                    .body(message_summary(id));
+            })
+            .await;
+        summary_mocks.push(mock);
+    }
+    let unread_mock = http
+        .mock_async(|when, then| {
+            when.method(PUT)
+                .path("/api/v1/messages")
+                .body(r#"{"IDs":["id-1"],"Read":false,"Search":null}"#);
+            then.status(200).body("ok");
+        })
+        .await;
+    let events = vec![
+        new_message_event("id-1", false),
+        r#"{ "Type": "update", "Data": { "ID": "id-1", "Read": true } }"#.to_string(),
+        r#"{ "Type": "stats", "Data": { "Total": 2, "Unread": 1 } }"#.to_string(),
+        new_message_event("id-2", true),
+    ];
+    let url = serve_events_with_http(events, &http).await;
+
+    let client = MailpitClient::builder(&url)
+        .preserve_read_status(true)
+        .build()
+        .unwrap();
+    let summaries: Vec<MessageSummary> = client
+        .watch_latest()
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        vec!["id-1", "id-2"],
+        summaries.iter().map(|s| s.id()).collect::<Vec<_>>()
+    );
+
+    for mock in summary_mocks {
+        mock.assert();
+    }
+    unread_mock.assert();
+}
+
+#[tokio::test]
+async fn subscribe_events_with_auth_success() {
+    let (url, server) = serve_events(Vec::new()).await;
+
+    let client = MailpitClient::new_with_auth(&url, "user", "secret").unwrap();
+    let events: Vec<EventMessage> = client
+        .subscribe_events()
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert!(events.is_empty());
+
+    let (_, authorization) = server.await.unwrap();
+    assert_eq!(Some("Basic dXNlcjpzZWNyZXQ=".to_string()), authorization);
+}
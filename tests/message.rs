@@ -1,13 +1,18 @@
+use std::time::Duration;
+
 use base64::{Engine, prelude::BASE64_STANDARD};
 use bytes::Bytes;
 use httpmock::{
-    Method::{GET, POST},
+    HttpMockRequest,
+    Method::{GET, POST, PUT},
     MockServer,
 };
 use mailpit_client::{
     MailpitClient,
+    error::Error,
     models::{
-        AddressObject, Attachment, MessageHeaders, MessageSummary, SendMessage, SendMessageResponse,
+        AddressObject, Attachment, IngestSource, MessageHeaders, MessageSummary,
+        ReleaseMessageResponse, SendMessage, SendMessageResponse,
     },
 };
 use pretty_assertions::{assert_eq, assert_str_eq};
@@ -102,6 +107,290 @@ async fn get_message_summary_success() {
     mock.assert();
 }
 
+#[tokio::test]
+async fn get_latest_message_success() {
+    let expected_response = r#"{
+      "Attachments": [],
+      "Bcc": [],
+      "Cc": [],
+      "Date": "1970-01-01T00:00:00.000Z",
+      "From": { "Address": "jane@example.com", "Name": "Jane Doe" },
+      "HTML": "",
+      "ID": "database-id",
+      "Inline": [],
+      "ListUnsubscribe": { "Errors": "", "Header": "", "HeaderPost": "", "Links": [] },
+      "MessageID": "latest@example.com",
+      "ReplyTo": [],
+      "ReturnPath": "",
+      "Size": 0,
+      "Subject": "Latest",
+      "Tags": [],
+      "Text": "",
+      "To": [],
+      "Username": ""
+    }"#;
+
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/message/latest");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(expected_response);
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let response = client.get_latest_message().await.unwrap();
+
+    let expected_response: MessageSummary = serde_json::from_str(expected_response).unwrap();
+    assert_eq!(&expected_response, &response);
+
+    mock.assert();
+}
+
+const PRESERVE_SUMMARY: &str = r#"{
+  "Attachments": [],
+  "Bcc": [],
+  "Cc": [],
+  "Date": "1970-01-01T00:00:00.000Z",
+  "From": { "Address": "jane@example.com", "Name": "Jane Doe" },
+  "HTML": "",
+  "ID": "database-id",
+  "Inline": [],
+  "ListUnsubscribe": { "Errors": "", "Header": "", "HeaderPost": "", "Links": [] },
+  "MessageID": "unread@example.com",
+  "ReplyTo": [],
+  "ReturnPath": "",
+  "Size": 0,
+  "Subject": "Unread",
+  "Tags": [],
+  "Text": "",
+  "To": [],
+  "Username": ""
+}"#;
+
+/// Message list containing `ids`, all sharing the same `Message-ID` and
+/// the read status `read`.
+fn preserve_list(ids: &[&str], read: bool) -> String {
+    let messages = ids
+        .iter()
+        .map(|id| {
+            format!(
+                r#"{{
+                  "Attachments": 0,
+                  "Bcc": [],
+                  "Cc": [],
+                  "Created": "1970-01-01T00:00:00.000Z",
+                  "From": {{ "Address": "jane@example.com", "Name": "Jane Doe" }},
+                  "ID": "{id}",
+                  "MessageID": "unread@example.com",
+                  "Read": {read},
+                  "ReplyTo": [],
+                  "Size": 0,
+                  "Snippet": "",
+                  "Subject": "Unread",
+                  "Tags": [],
+                  "To": [],
+                  "Username": ""
+                }}"#
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+
+    format!(
+        r#"{{
+          "messages": [{messages}],
+          "messages_count": {count},
+          "messages_unread": 0,
+          "start": 0,
+          "tags": [],
+          "total": {count},
+          "unread": 0
+        }}"#,
+        count = ids.len()
+    )
+}
+
+#[tokio::test]
+async fn get_message_summary_preserve_read_status_success() {
+    let server = MockServer::start_async().await;
+    let latest_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/messages")
+                .query_param("limit", "1");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(preserve_list(&["database-id"], false));
+        })
+        .await;
+    let summary_mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/message/database-id");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(PRESERVE_SUMMARY);
+        })
+        .await;
+    let unread_mock = server
+        .mock_async(|when, then| {
+            when.method(PUT)
+                .path("/api/v1/messages")
+                .body(r#"{"IDs":["database-id"],"Read":false,"Search":null}"#);
+            then.status(200).body("ok");
+        })
+        .await;
+
+    let client = MailpitClient::builder(&server.base_url())
+        .preserve_read_status(true)
+        .build()
+        .unwrap();
+    let response = client.get_latest_message().await.unwrap();
+
+    assert_eq!("database-id", response.id());
+
+    latest_mock.assert();
+    summary_mock.assert();
+    unread_mock.assert();
+}
+
+#[tokio::test]
+async fn get_message_summary_preserve_read_status_by_id_success() {
+    for read in [false, true] {
+        let server = MockServer::start_async().await;
+        let headers_mock = server
+            .mock_async(|when, then| {
+                when.method(GET).path("/api/v1/message/database-id/headers");
+                then.status(200)
+                    .header("content-type", "application/json")
+                    .body(r#"{"Message-Id": ["<unread@example.com>"]}"#);
+            })
+            .await;
+        // Another message shares the Message-ID, only the fetched one may
+        // be restored.
+        let search_mock = server
+            .mock_async(|when, then| {
+                when.method(GET)
+                    .path("/api/v1/search")
+                    .query_param("query", r#"message-id:"unread@example.com""#);
+                then.status(200)
+                    .header("content-type", "application/json")
+                    .body(preserve_list(&["other-id", "database-id"], read));
+            })
+            .await;
+        let summary_mock = server
+            .mock_async(|when, then| {
+                when.method(GET).path("/api/v1/message/database-id");
+                then.status(200)
+                    .header("content-type", "application/json")
+                    .body(PRESERVE_SUMMARY);
+            })
+            .await;
+        let unread_mock = server
+            .mock_async(|when, then| {
+                when.method(PUT)
+                    .path("/api/v1/messages")
+                    .body(r#"{"IDs":["database-id"],"Read":false,"Search":null}"#);
+                then.status(200).body("ok");
+            })
+            .await;
+
+        let client = MailpitClient::builder(&server.base_url())
+            .preserve_read_status(true)
+            .build()
+            .unwrap();
+        let response = client.get_message_summary("database-id").await.unwrap();
+
+        assert_eq!("database-id", response.id());
+
+        headers_mock.assert();
+        search_mock.assert();
+        summary_mock.assert();
+        unread_mock.assert_calls(if read { 0 } else { 1 });
+    }
+}
+
+#[tokio::test]
+async fn get_message_summary_preserve_read_status_without_message_id_success() {
+    let server = MockServer::start_async().await;
+    let headers_mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/message/database-id/headers");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(r#"{"Subject": ["Unread"]}"#);
+        })
+        .await;
+    let list_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/messages")
+                .query_param("start", "0");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(preserve_list(&["database-id"], false));
+        })
+        .await;
+    let summary_mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/message/database-id");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(PRESERVE_SUMMARY);
+        })
+        .await;
+    let unread_mock = server
+        .mock_async(|when, then| {
+            when.method(PUT)
+                .path("/api/v1/messages")
+                .body(r#"{"IDs":["database-id"],"Read":false,"Search":null}"#);
+            then.status(200).body("ok");
+        })
+        .await;
+
+    let client = MailpitClient::builder(&server.base_url())
+        .preserve_read_status(true)
+        .build()
+        .unwrap();
+    client.get_message_summary("database-id").await.unwrap();
+
+    headers_mock.assert();
+    list_mock.assert();
+    summary_mock.assert();
+    unread_mock.assert();
+}
+
+#[tokio::test]
+async fn get_message_raw_json_success() {
+    let expected_response = r#"{
+      "ID": "database-id",
+      "Subject": "string",
+      "Unknown": {
+        "Nested": [1, 2, 3]
+      }
+    }"#;
+
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/message/database-id");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(expected_response);
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let response = client.get_message_raw_json("database-id").await.unwrap();
+
+    let expected_response: serde_json::Value = serde_json::from_str(expected_response).unwrap();
+    assert_eq!(&expected_response, &response);
+
+    mock.assert();
+}
+
 #[tokio::test]
 async fn get_message_headers_success() {
     let expected_response = r#"{
@@ -133,163 +422,1112 @@ async fn get_message_headers_success() {
 }
 
 #[tokio::test]
-async fn get_message_attachment_success() {
-    let expected_response = Bytes::from("Hello!");
+async fn get_message_header_success() {
+    let expected_response = r#"{
+      "Dkim-Signature": [
+        "v=1; a=rsa-sha256; d=example.com"
+      ],
+      "Received-Spf": [
+        "pass"
+      ]
+    }"#;
 
     let server = MockServer::start_async().await;
     let mock = server
         .mock_async(|when, then| {
-            when.method(GET)
-                .path("/api/v1/message/database-id/part/part-id");
+            when.method(GET).path("/api/v1/message/latest/headers");
             then.status(200)
                 .header("content-type", "application/json")
-                .body(&expected_response);
+                .body(expected_response);
         })
         .await;
 
     let client = MailpitClient::new(&server.base_url()).unwrap();
-    let response = client
-        .get_message_attachment("database-id", "part-id")
+    let present = client
+        .get_message_header("latest", "DKIM-Signature")
+        .await
+        .unwrap();
+    let absent = client
+        .get_message_header("latest", "Authentication-Results")
         .await
         .unwrap();
 
-    assert_eq!(&expected_response, &response);
+    assert_eq!(
+        Some(vec!["v=1; a=rsa-sha256; d=example.com".to_string()]),
+        present
+    );
+    assert_eq!(None, absent);
 
-    mock.assert();
+    mock.assert_calls(2);
 }
 
 #[tokio::test]
-async fn get_message_attachment_image_thumbnail_success() {
-    let expected_response = Bytes::from("Hello!");
+async fn get_message_ingest_source_success() {
+    let smtp_response = r#"{
+      "Received": [
+        "from [127.0.0.1] (localhost [127.0.0.1]) by mailpit (Mailpit) with SMTP"
+      ],
+      "Subject": ["Hello"]
+    }"#;
+    let send_api_response = r#"{
+      "Subject": ["Hello"]
+    }"#;
 
     let server = MockServer::start_async().await;
-    let mock = server
+    let smtp_mock = server
         .mock_async(|when, then| {
-            when.method(GET)
-                .path("/api/v1/message/database-id/part/part-id/thumb");
+            when.method(GET).path("/api/v1/message/smtp-id/headers");
             then.status(200)
                 .header("content-type", "application/json")
-                .body(&expected_response);
+                .body(smtp_response);
+        })
+        .await;
+    let send_api_mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/message/send-api-id/headers");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(send_api_response);
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let smtp = client.get_message_ingest_source("smtp-id").await.unwrap();
+    let send_api = client
+        .get_message_ingest_source("send-api-id")
+        .await
+        .unwrap();
+
+    assert_eq!(IngestSource::Smtp, smtp);
+    assert_eq!(IngestSource::SendApi, send_api);
+
+    smtp_mock.assert();
+    send_api_mock.assert();
+}
+
+#[tokio::test]
+async fn get_message_headers_ordered_success() {
+    let source = "Received: from b.example.com by c.example.com\r\n\
+        Received: from a.example.com\r\n\tby b.example.com\r\n\
+        Subject: Hello\r\n\
+        X-Empty:\r\n\
+        \r\n\
+        Body: not a header\r\n";
+
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/message/database-id/raw");
+            then.status(200).body(source);
         })
         .await;
 
     let client = MailpitClient::new(&server.base_url()).unwrap();
     let response = client
-        .get_message_attachment_image_thumbnail("database-id", "part-id")
+        .get_message_headers_ordered("database-id")
         .await
         .unwrap();
 
-    assert_eq!(&expected_response, &response);
+    let expected_response = vec![
+        (
+            "Received".to_string(),
+            "from b.example.com by c.example.com".to_string(),
+        ),
+        (
+            "Received".to_string(),
+            "from a.example.com\tby b.example.com".to_string(),
+        ),
+        ("Subject".to_string(), "Hello".to_string()),
+        ("X-Empty".to_string(), "".to_string()),
+    ];
+    assert_eq!(expected_response, response);
 
     mock.assert();
 }
 
 #[tokio::test]
-async fn get_message_source_success() {
-    let expected_response = r#"Some plain text"#;
+async fn get_message_part_headers_success() {
+    let source = "From: john@example.com\r\n\
+        Content-Type: multipart/mixed; boundary=\"outer\"\r\n\
+        \r\n\
+        preamble\r\n\
+        --outer\r\n\
+        Content-Type: multipart/alternative; boundary=inner\r\n\
+        \r\n\
+        --inner\r\n\
+        Content-Type: text/plain\r\n\
+        \r\n\
+        Hello\r\n\
+        --inner\r\n\
+        Content-Type: text/html\r\n\
+        \r\n\
+        <p>Hello</p>\r\n\
+        --inner--\r\n\
+        --outer\r\n\
+        Content-Type: text/csv; name=\"report.csv\"\r\n\
+        Content-Disposition: attachment;\r\n\tfilename=\"report.csv\"\r\n\
+        X-Custom: first\r\n\
+        X-Custom: second\r\n\
+        \r\n\
+        a,b\r\n\
+        --outer--\r\n";
 
     let server = MockServer::start_async().await;
     let mock = server
         .mock_async(|when, then| {
             when.method(GET).path("/api/v1/message/database-id/raw");
-            then.status(200)
-                .header("content-type", "application/json")
-                .body(&expected_response);
+            then.status(200).body(source);
         })
         .await;
 
     let client = MailpitClient::new(&server.base_url()).unwrap();
-    let response = client.get_message_source("database-id").await.unwrap();
+    let attachment = client
+        .get_message_part_headers("database-id", "2")
+        .await
+        .unwrap();
+    let html = client
+        .get_message_part_headers("database-id", "1.2")
+        .await
+        .unwrap();
+    let missing = client
+        .get_message_part_headers("database-id", "3")
+        .await
+        .unwrap_err();
 
-    assert_str_eq!(expected_response, &response);
+    let expected_attachment: MessageHeaders = [
+        ("Content-Type", vec![r#"text/csv; name="report.csv""#]),
+        (
+            "Content-Disposition",
+            vec!["attachment;\tfilename=\"report.csv\""],
+        ),
+        ("X-Custom", vec!["first", "second"]),
+    ]
+    .into_iter()
+    .map(|(name, values)| {
+        (
+            name.to_string(),
+            values.into_iter().map(Into::into).collect(),
+        )
+    })
+    .collect();
+    assert_eq!(expected_attachment, attachment);
+    assert_eq!(
+        Some(&vec!["text/html".to_string()]),
+        html.get("Content-Type")
+    );
+    assert!(matches!(missing, Error::PartNotFound { part_id } if part_id == "3"));
 
-    mock.assert();
+    mock.assert_calls(3);
 }
 
 #[tokio::test]
-async fn post_release_message_success() {
-    let expected_request = r#"{"To":["user1@example.com","user2@example.com"]}"#;
+async fn get_message_attachment_success() {
+    let expected_response = Bytes::from("Hello!");
 
     let server = MockServer::start_async().await;
     let mock = server
         .mock_async(|when, then| {
-            when.method(POST)
-                .path("/api/v1/message/database-id/release")
-                .body(expected_request);
+            when.method(GET)
+                .path("/api/v1/message/database-id/part/part-id");
             then.status(200)
                 .header("content-type", "application/json")
-                .body("ok");
+                .body(&expected_response);
         })
         .await;
 
     let client = MailpitClient::new(&server.base_url()).unwrap();
     let response = client
-        .post_release_message("database-id", &["user1@example.com", "user2@example.com"])
+        .get_message_attachment("database-id", "part-id")
         .await
         .unwrap();
 
-    assert!(response);
+    assert_eq!(&expected_response, &response);
 
     mock.assert();
 }
 
 #[tokio::test]
-async fn post_send_message_success() {
-    let expected_request = "{\"Attachments\":[{\"Content\":\"iVBORw0KGgoAAAANSUhEUgAAAEEAAAA8CAMAAAAOlSdoAAAACXBIWXMAAAHrAAAB6wGM2bZBAAAAS1BMVEVHcEwRfnUkZ2gAt4UsSF8At4UtSV4At4YsSV4At4YsSV8At4YsSV4At4YsSV4sSV4At4YsSV4At4YtSV4At4YsSV4At4YtSV8At4YsUWYNAAAAGHRSTlMAAwoXGiktRE5dbnd7kpOlr7zJ0d3h8PD8PCSRAAACWUlEQVR42pXT4ZaqIBSG4W9rhqQYocG+/ys9Y0Z0Br+x3j8zaxUPewFh65K+7yrIMeIY4MT3wPfEJCidKXEMnLaVkxDiELiMz4WEOAZSFghxBIypCOlKiAMgXfIqTnBgSm8CIQ6BImxEUxEckClVQiHGj4Ba4AQHikAIClwTE9KtIghAhUJwoLkmLnCiAHJLRKgIMsEtVUKbBUIwoAg2C4QgQBE6l4VCnApBgSKYLLApCnCa0+96AEMW2BQcmC+Pr3nfp7o5Exy49gIADcIqUELGfeA+bp93LmAJp8QJoEcN3C7NY3sbVANixMyI0nku20/n5/ZRf3KI2k6JEDWQtxcbdGuAqu3TAXG+/799Oyyas1B1MnMiA+XyxHp9q0PUKGPiRAau1fZbLRZV09wZcT8/gHk8QQAxXn8VgaDqcUmU6O/r28nbVwXAqca2mRNtPAF5+zoP2MeN9Fy4NgC6RfcbgE7XITBRYTtOE3U3C2DVff7pk+PkUxgAbvtnPXJaD6DxulMLwOhPS/M3MQkgg1ZFrIXnmfaZoOfpKiFgzeZD/WuKqQEGrfJYkyWf6vlG3xUgTuscnkNkQsb599q124kdpMUjCa/XARHs1gZymVtGt3wLkiFv8rUgTxitYCex5EVGec0Y9VmoDTFBSQte2TfXGXlf7hbdaUM9Sk7fisEN9qfBBTK+FZcvM9fQSdkl2vj4W2oX/bRogO3XasiNH7R0eW7fgRM834ImTg+Lg6BEnx4vz81rhr+MYPBBQg1v8GndEOrthxaCTxNAOut8WKLGZQl+MPz88Q9tAO/hVuSeqQAAAABJRU5ErkJggg==\",\"ContentID\":\"mailpit-logo\",\"ContentType\":\"image/png\",\"Filename\":\"mailpit.png\"}],\"Bcc\":[\"jack@example.com\"],\"Cc\":[{\"Email\":\"manager@example.com\",\"Name\":\"Manager\"}],\"From\":{\"Email\":\"john@example.com\",\"Name\":\"John Doe\"},\"HTML\":\"<div style=\\\"text-align:center\\\"><p style=\\\"font-family: arial; font-size: 24px;\\\">Mailpit is <b>awesome</b>!</p><p><img src=\\\"cid:mailpit-logo\\\" /></p></div>\",\"Headers\":{\"X-IP\":\"1.2.3.4\"},\"ReplyTo\":[{\"Email\":\"secretary@example.com\",\"Name\":\"Secretary\"}],\"Subject\":\"Mailpit message via the HTTP API\",\"Tags\":[\"Tag 1\",\"Tag 2\"],\"Text\":\"Mailpit is awesome!\",\"To\":[{\"Email\":\"jane@example.com\",\"Name\":\"Jane Doe\"}]}";
-    let expected_response = r#"{
-      "ID": "iAfZVVe2UQfNSG5BAjgYwa"
-    }"#;
+async fn get_message_attachment_encoded_id_success() {
+    let expected_response = Bytes::from("Hello!");
 
     let server = MockServer::start_async().await;
     let mock = server
         .mock_async(|when, then| {
-            when.method(POST)
-                .path("/api/v1/send")
-                .body(expected_request);
-            then.status(200)
-                .header("content-type", "application/json")
-                .body(expected_response);
+            when.is_true(|req: &HttpMockRequest| {
+                req.uri().path() == "/api/v1/message/a%2Fb%20c/part/1%2F2"
+            });
+            then.status(200).body(&expected_response);
         })
         .await;
 
     let client = MailpitClient::new(&server.base_url()).unwrap();
-    let content = BASE64_STANDARD.decode("iVBORw0KGgoAAAANSUhEUgAAAEEAAAA8CAMAAAAOlSdoAAAACXBIWXMAAAHrAAAB6wGM2bZBAAAAS1BMVEVHcEwRfnUkZ2gAt4UsSF8At4UtSV4At4YsSV4At4YsSV8At4YsSV4At4YsSV4sSV4At4YsSV4At4YtSV4At4YsSV4At4YtSV8At4YsUWYNAAAAGHRSTlMAAwoXGiktRE5dbnd7kpOlr7zJ0d3h8PD8PCSRAAACWUlEQVR42pXT4ZaqIBSG4W9rhqQYocG+/ys9Y0Z0Br+x3j8zaxUPewFh65K+7yrIMeIY4MT3wPfEJCidKXEMnLaVkxDiELiMz4WEOAZSFghxBIypCOlKiAMgXfIqTnBgSm8CIQ6BImxEUxEckClVQiHGj4Ba4AQHikAIClwTE9KtIghAhUJwoLkmLnCiAHJLRKgIMsEtVUKbBUIwoAg2C4QgQBE6l4VCnApBgSKYLLApCnCa0+96AEMW2BQcmC+Pr3nfp7o5Exy49gIADcIqUELGfeA+bp93LmAJp8QJoEcN3C7NY3sbVANixMyI0nku20/n5/ZRf3KI2k6JEDWQtxcbdGuAqu3TAXG+/799Oyyas1B1MnMiA+XyxHp9q0PUKGPiRAau1fZbLRZV09wZcT8/gHk8QQAxXn8VgaDqcUmU6O/r28nbVwXAqca2mRNtPAF5+zoP2MeN9Fy4NgC6RfcbgE7XITBRYTtOE3U3C2DVff7pk+PkUxgAbvtnPXJaD6DxulMLwOhPS/M3MQkgg1ZFrIXnmfaZoOfpKiFgzeZD/WuKqQEGrfJYkyWf6vlG3xUgTuscnkNkQsb599q124kdpMUjCa/XARHs1gZymVtGt3wLkiFv8rUgTxitYCex5EVGec0Y9VmoDTFBSQte2TfXGXlf7hbdaUM9Sk7fisEN9qfBBTK+FZcvM9fQSdkl2vj4W2oX/bRogO3XasiNH7R0eW7fgRM834ImTg+Lg6BEnx4vz81rhr+MYPBBQg1v8GndEOrthxaCTxNAOut8WKLGZQl+MPz88Q9tAO/hVuSeqQAAAABJRU5ErkJggg==").unwrap();
-    let attachment = Attachment::builder()
-        .content(&content)
-        .content_id("mailpit-logo")
-        .content_type("image/png")
-        .filename("mailpit.png")
-        .build()
-        .unwrap();
-    let request = SendMessage {
-        attachments: Some(vec![attachment]),
-        bcc: Some(vec!["jack@example.com".to_string()]),
-        cc: Some(vec![AddressObject {
-            address: "manager@example.com".to_string(),
-            name: Some("Manager".to_string()),
-        }]),
-        from: AddressObject {
-            address: "john@example.com".to_string(),
-            name: Some("John Doe".to_string()),
-        },
-        html: "<div style=\"text-align:center\"><p style=\"font-family: arial; font-size: 24px;\">Mailpit is <b>awesome</b>!</p><p><img src=\"cid:mailpit-logo\" /></p></div>".to_string(),
-        headers: Some([("X-IP".to_string(), "1.2.3.4".to_string())].into_iter().collect()),
-        reply_to: Some(vec![AddressObject {
-            address: "secretary@example.com".to_string(),
-            name: Some("Secretary".to_string()),
-        }]),
-        subject: "Mailpit message via the HTTP API".to_string(),
-        tags: vec!["Tag 1".to_string(), "Tag 2".to_string()],
-        text: "Mailpit is awesome!".to_string(),
-        to: vec![AddressObject {
-            address: "jane@example.com".to_string(),
-            name: Some("Jane Doe".to_string()),
-        }],
-    };
-    let response = client.post_send_message(request).await.unwrap();
+    let response = client.get_message_attachment("a/b c", "1/2").await.unwrap();
 
-    let expected_response: SendMessageResponse = serde_json::from_str(expected_response).unwrap();
     assert_eq!(&expected_response, &response);
 
     mock.assert();
 }
+
+#[tokio::test]
+async fn get_message_attachment_sha256_success() {
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/message/database-id/part/part-id");
+            then.status(200).body("Hello!");
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let digest = client
+        .get_message_attachment_sha256("database-id", "part-id")
+        .await
+        .unwrap();
+
+    assert_str_eq!(
+        "334d016f755cd6dc58c53a86e183882f8ec14f52fb05345887c8a5edd42c87b7",
+        digest
+    );
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn get_message_attachment_range_success() {
+    let server = MockServer::start_async().await;
+    let partial_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/message/database-id/part/part-id")
+                .header("range", "bytes=2-4");
+            then.status(206).body("llo");
+        })
+        .await;
+    let open_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/message/database-id/part/part-id")
+                .header("range", "bytes=6-");
+            then.status(416);
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let response = client
+        .get_message_attachment_range("database-id", "part-id", 2, Some(4))
+        .await
+        .unwrap();
+    let unsatisfiable = client
+        .get_message_attachment_range("database-id", "part-id", 6, None)
+        .await;
+
+    assert_eq!(Bytes::from("llo"), response);
+    assert!(matches!(
+        unsatisfiable,
+        Err(Error::HttpFailure { status: 416, .. })
+    ));
+
+    partial_mock.assert();
+    open_mock.assert();
+}
+
+#[tokio::test]
+async fn get_message_attachment_size_success() {
+    let summary = r#"{
+      "Attachments": [
+        { "ContentID": "", "ContentType": "application/pdf", "FileName": "a.pdf", "PartID": "2", "Size": 1024 }
+      ],
+      "Bcc": [],
+      "Cc": [],
+      "Date": "1970-01-01T00:00:00.000Z",
+      "From": { "Address": "jane@example.com", "Name": "Jane Doe" },
+      "HTML": "",
+      "ID": "database-id",
+      "Inline": [
+        { "ContentID": "logo", "ContentType": "image/png", "FileName": "logo.png", "PartID": "1.2", "Size": 512 }
+      ],
+      "ListUnsubscribe": { "Errors": "", "Header": "", "HeaderPost": "", "Links": [] },
+      "MessageID": "message-id",
+      "ReplyTo": [],
+      "ReturnPath": "",
+      "Size": 4096,
+      "Subject": "string",
+      "Tags": [],
+      "Text": "",
+      "To": [],
+      "Username": ""
+    }"#;
+
+    let server = MockServer::start_async().await;
+    let latest_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/messages")
+                .query_param("limit", "1");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(preserve_list(&["database-id"], true));
+        })
+        .await;
+    let summary_mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/message/latest");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(summary);
+        })
+        .await;
+    let part_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/message/database-id/part/1.1")
+                .header("range", "bytes=0-0");
+            then.status(206)
+                .header("content-range", "bytes 0-0/2048")
+                .body("x");
+        })
+        .await;
+
+    let client = MailpitClient::builder(&server.base_url())
+        .preserve_read_status(true)
+        .build()
+        .unwrap();
+    let attachment = client
+        .get_message_attachment_size("latest", "2")
+        .await
+        .unwrap();
+    let inline = client
+        .get_message_attachment_size("latest", "1.2")
+        .await
+        .unwrap();
+    let nested = client
+        .get_message_attachment_size("latest", "1.1")
+        .await
+        .unwrap();
+
+    assert_eq!(Some(1024), attachment);
+    assert_eq!(Some(512), inline);
+    assert_eq!(Some(2048), nested);
+
+    latest_mock.assert_calls(3);
+    summary_mock.assert_calls(3);
+    part_mock.assert();
+}
+
+#[tokio::test]
+async fn get_message_attachment_size_range_success() {
+    let server = MockServer::start_async().await;
+    let summary_mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/message/database-id");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(PRESERVE_SUMMARY);
+        })
+        .await;
+    let full_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/message/database-id/part/2")
+                .header("range", "bytes=0-0");
+            then.status(200).body(vec![0; 1024]);
+        })
+        .await;
+    let not_allowed_mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/message/database-id/part/3");
+            then.status(405);
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let ignored_range = client
+        .get_message_attachment_size("database-id", "2")
+        .await
+        .unwrap();
+    let not_allowed = client
+        .get_message_attachment_size("database-id", "3")
+        .await
+        .unwrap();
+
+    assert_eq!(Some(1024), ignored_range);
+    assert_eq!(None, not_allowed);
+
+    summary_mock.assert_calls(2);
+    full_mock.assert();
+    not_allowed_mock.assert();
+}
+
+#[cfg(feature = "zip")]
+#[tokio::test]
+async fn get_message_attachments_zip_success() {
+    use std::io::{Cursor, Read};
+
+    let summary = r#"{
+      "Attachments": [
+        { "ContentID": "", "ContentType": "text/plain", "FileName": "report.txt", "PartID": "2", "Size": 6 },
+        { "ContentID": "", "ContentType": "text/plain", "FileName": "report.txt", "PartID": "3", "Size": 6 }
+      ],
+      "Date": "1970-01-01T00:00:00.000Z",
+      "From": { "Address": "string", "Name": "string" },
+      "HTML": "string",
+      "ID": "database-id",
+      "Inline": [
+        { "ContentID": "logo", "ContentType": "image/png", "FileName": "logo.png", "PartID": "1.2", "Size": 4 }
+      ],
+      "ListUnsubscribe": { "Errors": "", "Header": "", "HeaderPost": "", "Links": [] },
+      "MessageID": "string",
+      "ReplyTo": [],
+      "ReturnPath": "string",
+      "Size": 0,
+      "Subject": "string",
+      "Tags": [],
+      "Text": "string",
+      "To": [],
+      "Username": "string"
+    }"#;
+
+    let server = MockServer::start_async().await;
+    let summary_mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/message/latest");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(summary);
+        })
+        .await;
+    let mut part_mocks = Vec::new();
+    for (part_id, content) in [("2", "first!"), ("3", "second"), ("1.2", "logo")] {
+        part_mocks.push(
+            server
+                .mock_async(|when, then| {
+                    when.method(GET)
+                        .path(format!("/api/v1/message/database-id/part/{part_id}"));
+                    then.status(200).body(content);
+                })
+                .await,
+        );
+    }
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let response = client.get_message_attachments_zip("latest").await.unwrap();
+
+    let mut archive = zip::ZipArchive::new(Cursor::new(response)).unwrap();
+    let mut files = Vec::new();
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i).unwrap();
+        let mut content = String::new();
+        file.read_to_string(&mut content).unwrap();
+        files.push((file.name().to_string(), content));
+    }
+    assert_eq!(
+        vec![
+            ("report.txt".to_string(), "first!".to_string()),
+            ("report (1).txt".to_string(), "second".to_string()),
+            ("logo.png".to_string(), "logo".to_string()),
+        ],
+        files
+    );
+
+    summary_mock.assert();
+    for mock in part_mocks {
+        mock.assert();
+    }
+}
+
+#[tokio::test]
+async fn get_message_attachment_image_thumbnail_success() {
+    let expected_response = Bytes::from("Hello!");
+
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/message/database-id/part/part-id/thumb");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(&expected_response);
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let response = client
+        .get_message_attachment_image_thumbnail("database-id", "part-id")
+        .await
+        .unwrap();
+
+    assert_eq!(&expected_response, &response);
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn get_message_source_success() {
+    let expected_response = r#"Some plain text"#;
+
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/message/database-id/raw");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(expected_response);
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let response = client.get_message_source("database-id").await.unwrap();
+
+    assert_str_eq!(expected_response, &response);
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn get_message_eml_tee_success() {
+    let expected_response = "Subject: Hello\r\n\r\nSome plain text";
+
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/message/database-id/raw");
+            then.status(200).body(expected_response);
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let mut written = Vec::new();
+    let response = client
+        .get_message_eml_tee("database-id", &mut written)
+        .await
+        .unwrap();
+
+    assert_eq!(Bytes::from(expected_response), response);
+    assert_eq!(&written, &response);
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn post_release_message_success() {
+    let expected_request = r#"{"To":["user1@example.com","user2@example.com"]}"#;
+
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(POST)
+                .path("/api/v1/message/database-id/release")
+                .body(expected_request);
+            then.status(200)
+                .header("content-type", "application/json")
+                .body("ok");
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let response = client
+        .post_release_message("database-id", &["user1@example.com", "user2@example.com"])
+        .await
+        .unwrap();
+
+    assert!(response);
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn post_release_message_verbose_success() {
+    let expected_request = r#"{"To":["user1@example.com","user2@example.com"]}"#;
+    let expected_response = r#"{
+      "Message": "released",
+      "To": ["user1@example.com"]
+    }"#;
+
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(POST)
+                .path("/api/v1/message/database-id/release")
+                .body(expected_request);
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(expected_response);
+        })
+        .await;
+    let ok_mock = server
+        .mock_async(|when, then| {
+            when.method(POST).path("/api/v1/message/latest/release");
+            then.status(200).body("ok");
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let response = client
+        .post_release_message_verbose("database-id", &["user1@example.com", "user2@example.com"])
+        .await
+        .unwrap();
+    let ok_response = client
+        .post_release_message_verbose("latest", &["user1@example.com"])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        ReleaseMessageResponse {
+            message: "released".to_string(),
+            to: vec!["user1@example.com".to_string()],
+        },
+        response
+    );
+    assert_eq!(
+        ReleaseMessageResponse {
+            message: "ok".to_string(),
+            to: vec!["user1@example.com".to_string()],
+        },
+        ok_response
+    );
+
+    mock.assert();
+    ok_mock.assert();
+}
+
+#[tokio::test]
+async fn post_release_message_relay_not_configured() {
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(POST)
+                .path("/api/v1/message/database-id/release");
+            then.status(400).body("message relay is disabled");
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let error = client
+        .post_release_message("database-id", &["user1@example.com"])
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, Error::RelayNotConfigured));
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn post_send_message_success() {
+    let expected_request = "{\"Attachments\":[{\"Content\":\"iVBORw0KGgoAAAANSUhEUgAAAEEAAAA8CAMAAAAOlSdoAAAACXBIWXMAAAHrAAAB6wGM2bZBAAAAS1BMVEVHcEwRfnUkZ2gAt4UsSF8At4UtSV4At4YsSV4At4YsSV8At4YsSV4At4YsSV4sSV4At4YsSV4At4YtSV4At4YsSV4At4YtSV8At4YsUWYNAAAAGHRSTlMAAwoXGiktRE5dbnd7kpOlr7zJ0d3h8PD8PCSRAAACWUlEQVR42pXT4ZaqIBSG4W9rhqQYocG+/ys9Y0Z0Br+x3j8zaxUPewFh65K+7yrIMeIY4MT3wPfEJCidKXEMnLaVkxDiELiMz4WEOAZSFghxBIypCOlKiAMgXfIqTnBgSm8CIQ6BImxEUxEckClVQiHGj4Ba4AQHikAIClwTE9KtIghAhUJwoLkmLnCiAHJLRKgIMsEtVUKbBUIwoAg2C4QgQBE6l4VCnApBgSKYLLApCnCa0+96AEMW2BQcmC+Pr3nfp7o5Exy49gIADcIqUELGfeA+bp93LmAJp8QJoEcN3C7NY3sbVANixMyI0nku20/n5/ZRf3KI2k6JEDWQtxcbdGuAqu3TAXG+/799Oyyas1B1MnMiA+XyxHp9q0PUKGPiRAau1fZbLRZV09wZcT8/gHk8QQAxXn8VgaDqcUmU6O/r28nbVwXAqca2mRNtPAF5+zoP2MeN9Fy4NgC6RfcbgE7XITBRYTtOE3U3C2DVff7pk+PkUxgAbvtnPXJaD6DxulMLwOhPS/M3MQkgg1ZFrIXnmfaZoOfpKiFgzeZD/WuKqQEGrfJYkyWf6vlG3xUgTuscnkNkQsb599q124kdpMUjCa/XARHs1gZymVtGt3wLkiFv8rUgTxitYCex5EVGec0Y9VmoDTFBSQte2TfXGXlf7hbdaUM9Sk7fisEN9qfBBTK+FZcvM9fQSdkl2vj4W2oX/bRogO3XasiNH7R0eW7fgRM834ImTg+Lg6BEnx4vz81rhr+MYPBBQg1v8GndEOrthxaCTxNAOut8WKLGZQl+MPz88Q9tAO/hVuSeqQAAAABJRU5ErkJggg==\",\"ContentID\":\"mailpit-logo\",\"ContentType\":\"image/png\",\"Filename\":\"mailpit.png\"}],\"Bcc\":[\"jack@example.com\"],\"Cc\":[{\"Email\":\"manager@example.com\",\"Name\":\"Manager\"}],\"From\":{\"Email\":\"john@example.com\",\"Name\":\"John Doe\"},\"HTML\":\"<div style=\\\"text-align:center\\\"><p style=\\\"font-family: arial; font-size: 24px;\\\">Mailpit is <b>awesome</b>!</p><p><img src=\\\"cid:mailpit-logo\\\" /></p></div>\",\"Headers\":{\"X-IP\":\"1.2.3.4\"},\"ReplyTo\":[{\"Email\":\"secretary@example.com\",\"Name\":\"Secretary\"}],\"Subject\":\"Mailpit message via the HTTP API\",\"Tags\":[\"Tag 1\",\"Tag 2\"],\"Text\":\"Mailpit is awesome!\",\"To\":[{\"Email\":\"jane@example.com\",\"Name\":\"Jane Doe\"}]}";
+    let expected_response = r#"{
+      "ID": "iAfZVVe2UQfNSG5BAjgYwa"
+    }"#;
+
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(POST)
+                .path("/api/v1/send")
+                .body(expected_request);
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(expected_response);
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let content = BASE64_STANDARD.decode("iVBORw0KGgoAAAANSUhEUgAAAEEAAAA8CAMAAAAOlSdoAAAACXBIWXMAAAHrAAAB6wGM2bZBAAAAS1BMVEVHcEwRfnUkZ2gAt4UsSF8At4UtSV4At4YsSV4At4YsSV8At4YsSV4At4YsSV4sSV4At4YsSV4At4YtSV4At4YsSV4At4YtSV8At4YsUWYNAAAAGHRSTlMAAwoXGiktRE5dbnd7kpOlr7zJ0d3h8PD8PCSRAAACWUlEQVR42pXT4ZaqIBSG4W9rhqQYocG+/ys9Y0Z0Br+x3j8zaxUPewFh65K+7yrIMeIY4MT3wPfEJCidKXEMnLaVkxDiELiMz4WEOAZSFghxBIypCOlKiAMgXfIqTnBgSm8CIQ6BImxEUxEckClVQiHGj4Ba4AQHikAIClwTE9KtIghAhUJwoLkmLnCiAHJLRKgIMsEtVUKbBUIwoAg2C4QgQBE6l4VCnApBgSKYLLApCnCa0+96AEMW2BQcmC+Pr3nfp7o5Exy49gIADcIqUELGfeA+bp93LmAJp8QJoEcN3C7NY3sbVANixMyI0nku20/n5/ZRf3KI2k6JEDWQtxcbdGuAqu3TAXG+/799Oyyas1B1MnMiA+XyxHp9q0PUKGPiRAau1fZbLRZV09wZcT8/gHk8QQAxXn8VgaDqcUmU6O/r28nbVwXAqca2mRNtPAF5+zoP2MeN9Fy4NgC6RfcbgE7XITBRYTtOE3U3C2DVff7pk+PkUxgAbvtnPXJaD6DxulMLwOhPS/M3MQkgg1ZFrIXnmfaZoOfpKiFgzeZD/WuKqQEGrfJYkyWf6vlG3xUgTuscnkNkQsb599q124kdpMUjCa/XARHs1gZymVtGt3wLkiFv8rUgTxitYCex5EVGec0Y9VmoDTFBSQte2TfXGXlf7hbdaUM9Sk7fisEN9qfBBTK+FZcvM9fQSdkl2vj4W2oX/bRogO3XasiNH7R0eW7fgRM834ImTg+Lg6BEnx4vz81rhr+MYPBBQg1v8GndEOrthxaCTxNAOut8WKLGZQl+MPz88Q9tAO/hVuSeqQAAAABJRU5ErkJggg==").unwrap();
+    let attachment = Attachment::builder()
+        .content(&content)
+        .content_id("mailpit-logo")
+        .content_type("image/png")
+        .filename("mailpit.png")
+        .build()
+        .unwrap();
+    let request = SendMessage {
+        attachments: Some(vec![attachment]),
+        bcc: Some(vec![AddressObject {
+            address: "jack@example.com".to_string(),
+            name: Some("Jack".to_string()),
+        }]),
+        cc: Some(vec![AddressObject {
+            address: "manager@example.com".to_string(),
+            name: Some("Manager".to_string()),
+        }]),
+        from: AddressObject {
+            address: "john@example.com".to_string(),
+            name: Some("John Doe".to_string()),
+        },
+        html: "<div style=\"text-align:center\"><p style=\"font-family: arial; font-size: 24px;\">Mailpit is <b>awesome</b>!</p><p><img src=\"cid:mailpit-logo\" /></p></div>".to_string(),
+        headers: Some([("X-IP".to_string(), "1.2.3.4".to_string())].into_iter().collect()),
+        reply_to: Some(vec![AddressObject {
+            address: "secretary@example.com".to_string(),
+            name: Some("Secretary".to_string()),
+        }]),
+        subject: "Mailpit message via the HTTP API".to_string(),
+        tags: vec!["Tag 1".to_string(), "Tag 2".to_string()],
+        text: "Mailpit is awesome!".to_string(),
+        to: vec![AddressObject {
+            address: "jane@example.com".to_string(),
+            name: Some("Jane Doe".to_string()),
+        }],
+    };
+    let response = client.post_send_message(request).await.unwrap();
+
+    let expected_response: SendMessageResponse = serde_json::from_str(expected_response).unwrap();
+    assert_eq!(&expected_response, &response);
+
+    mock.assert();
+}
+
+#[test]
+fn send_message_serialization_deterministic_success() {
+    let message = SendMessage::builder(AddressObject {
+        address: "john@example.com".to_string(),
+        name: None,
+    })
+    .header("X-Zulu", "3")
+    .header("X-Alpha", "1")
+    .header("X-Mike", "2")
+    .subject("Hello")
+    .build();
+
+    let expected = r#"{"Attachments":null,"Bcc":null,"Cc":null,"From":{"Email":"john@example.com","Name":null},"HTML":"","Headers":{"X-Alpha":"1","X-Mike":"2","X-Zulu":"3"},"ReplyTo":null,"Subject":"Hello","Tags":[],"Text":"","To":[]}"#;
+    assert_str_eq!(expected, serde_json::to_string(&message).unwrap());
+}
+
+#[tokio::test]
+async fn post_send_message_in_reply_to_success() {
+    let expected_request = r#"{
+      "Headers": {
+        "In-Reply-To": "<original@example.com>",
+        "References": "<root@example.com> <original@example.com>"
+      }
+    }"#;
+    let expected_response = r#"{
+      "ID": "iAfZVVe2UQfNSG5BAjgYwa"
+    }"#;
+
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(POST)
+                .path("/api/v1/send")
+                .json_body_includes(expected_request);
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(expected_response);
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let original: MessageSummary = serde_json::from_str(
+        r#"{
+          "Attachments": [],
+          "Bcc": [],
+          "Cc": [],
+          "Date": "1970-01-01T00:00:00.000Z",
+          "From": { "Address": "jane@example.com", "Name": "Jane Doe" },
+          "HTML": "",
+          "ID": "database-id",
+          "Inline": [],
+          "ListUnsubscribe": { "Errors": "", "Header": "", "HeaderPost": "", "Links": [] },
+          "MessageID": "original@example.com",
+          "ReplyTo": [],
+          "ReturnPath": "",
+          "Size": 0,
+          "Subject": "Hello",
+          "Tags": [],
+          "Text": "",
+          "To": [{ "Address": "john@example.com", "Name": "John Doe" }],
+          "Username": ""
+        }"#,
+    )
+    .unwrap();
+    let request = SendMessage::builder(AddressObject {
+        address: "john@example.com".to_string(),
+        name: Some("John Doe".to_string()),
+    })
+    .to(AddressObject {
+        address: "jane@example.com".to_string(),
+        name: Some("Jane Doe".to_string()),
+    })
+    .subject("Re: Hello")
+    .text("Hello back!")
+    .header("References", "<root@example.com>")
+    .in_reply_to(&original)
+    .build();
+    let response = client.post_send_message(request).await.unwrap();
+
+    let expected_response: SendMessageResponse = serde_json::from_str(expected_response).unwrap();
+    assert_eq!(&expected_response, &response);
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn send_many_success() {
+    let server = MockServer::start_async().await;
+    for (subject, id, delay) in [("First", "id-1", 200), ("Second", "id-2", 0)] {
+        server
+            .mock_async(|when, then| {
+                when.method(POST)
+                    .path("/api/v1/send")
+                    .json_body_includes(format!(r#"{{"Subject": "{subject}"}}"#));
+                then.status(200)
+                    .header("content-type", "application/json")
+                    .delay(Duration::from_millis(delay))
+                    .body(format!(r#"{{"ID": "{id}"}}"#));
+            })
+            .await;
+    }
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let messages = ["First", "Second"]
+        .into_iter()
+        .map(|subject| {
+            SendMessage::builder(AddressObject {
+                address: "john@example.com".to_string(),
+                name: None,
+            })
+            .subject(subject)
+            .build()
+        })
+        .collect();
+    let responses = client.send_many(messages, 2).await.unwrap();
+
+    let ids: Vec<_> = responses.iter().map(|r| r.id.as_str()).collect();
+    assert_eq!(vec!["id-1", "id-2"], ids);
+}
+
+#[tokio::test]
+async fn send_many_failure() {
+    let server = MockServer::start_async().await;
+    server
+        .mock_async(|when, then| {
+            when.method(POST)
+                .path("/api/v1/send")
+                .json_body_includes(r#"{"Subject": "Invalid"}"#);
+            then.status(400).body(r#"{"Error": "invalid message"}"#);
+        })
+        .await;
+    server
+        .mock_async(|when, then| {
+            when.method(POST).path("/api/v1/send");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(r#"{"ID": "id-1"}"#);
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let messages = ["Valid", "Invalid"]
+        .into_iter()
+        .map(|subject| {
+            SendMessage::builder(AddressObject {
+                address: "john@example.com".to_string(),
+                name: None,
+            })
+            .subject(subject)
+            .build()
+        })
+        .collect();
+    let result = client.send_many(messages, 1).await;
+
+    assert!(matches!(
+        result,
+        Err(Error::SendManyFailed { index: 1, source })
+            if matches!(*source, Error::HttpFailure { status: 400, .. })
+    ));
+}
+
+#[tokio::test]
+async fn post_send_message_default_send_tag_success() {
+    let expected_request = r#"{
+      "Tags": ["Tag 1", "run-42", "cleanup"]
+    }"#;
+    let expected_response = r#"{
+      "ID": "iAfZVVe2UQfNSG5BAjgYwa"
+    }"#;
+
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(POST)
+                .path("/api/v1/send")
+                .json_body_includes(expected_request);
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(expected_response);
+        })
+        .await;
+
+    let client = MailpitClient::builder(&server.base_url())
+        .default_send_tag("run-42")
+        .default_send_tag("cleanup")
+        .build()
+        .unwrap();
+    let request = SendMessage::builder(AddressObject {
+        address: "john@example.com".to_string(),
+        name: Some("John Doe".to_string()),
+    })
+    .to(AddressObject {
+        address: "jane@example.com".to_string(),
+        name: Some("Jane Doe".to_string()),
+    })
+    .subject("Tagged")
+    .tag("Tag 1")
+    .tag("run-42")
+    .build();
+    let response = client.post_send_message(request).await.unwrap();
+
+    let expected_response: SendMessageResponse = serde_json::from_str(expected_response).unwrap();
+    assert_eq!(&expected_response, &response);
+
+    mock.assert();
+}
+
+#[test]
+fn address_object_from_str_success() {
+    let cases = [
+        ("jane@example.com", "jane@example.com", None),
+        ("<jane@example.com>", "jane@example.com", None),
+        (
+            "Jane Doe <jane@example.com>",
+            "jane@example.com",
+            Some("Jane Doe"),
+        ),
+        (
+            r#""Doe, Jane" <jane@example.com>"#,
+            "jane@example.com",
+            Some("Doe, Jane"),
+        ),
+    ];
+
+    for (input, address, name) in cases {
+        assert_eq!(
+            AddressObject {
+                address: address.to_string(),
+                name: name.map(str::to_string),
+            },
+            input.parse().unwrap()
+        );
+    }
+}
+
+#[test]
+fn address_object_from_str_failure() {
+    for input in ["", "Jane Doe", "Jane Doe <jane at example.com>"] {
+        assert!(matches!(
+            input.parse::<AddressObject>(),
+            Err(Error::InvalidAddress(_))
+        ));
+    }
+}
+
+#[test]
+fn address_object_from_success() {
+    let jane = |name: Option<&str>| AddressObject {
+        address: "jane@example.com".to_string(),
+        name: name.map(str::to_string),
+    };
+
+    assert_eq!(jane(None), AddressObject::from("jane@example.com"));
+    assert_eq!(
+        jane(None),
+        AddressObject::from("jane@example.com".to_string())
+    );
+    assert_eq!(
+        jane(Some("Jane")),
+        AddressObject::from(("jane@example.com", "Jane"))
+    );
+    assert_eq!(
+        jane(Some("Jane")),
+        AddressObject::from(("jane@example.com", Some("Jane")))
+    );
+    assert_eq!(jane(None), AddressObject::from(("jane@example.com", None)));
+}
+
+#[test]
+fn attachment_getters_success() {
+    let inline = Attachment::builder()
+        .content(b"logo")
+        .content_id("mailpit-logo")
+        .content_type("image/png")
+        .filename("mailpit.png")
+        .build()
+        .unwrap();
+    let attached = Attachment::builder()
+        .content(b"report")
+        .filename("report.pdf")
+        .build()
+        .unwrap();
+
+    assert_eq!("mailpit.png", inline.filename());
+    assert_eq!(Some("image/png"), inline.content_type());
+    assert_eq!(Some("mailpit-logo"), inline.content_id());
+    assert!(inline.is_inline());
+    assert_eq!(4, inline.content_len());
+
+    assert_eq!("report.pdf", attached.filename());
+    assert_eq!(None, attached.content_type());
+    assert_eq!(None, attached.content_id());
+    assert!(!attached.is_inline());
+    assert_eq!(6, attached.content_len());
+}
+
+#[test]
+fn send_message_builder_address_conversions_success() {
+    let message = SendMessage::builder(("john@example.com", "John Doe"))
+        .to("jane@example.com")
+        .cc(("bob@example.com", None))
+        .bcc("jack@example.com".to_string())
+        .build();
+
+    assert_str_eq!(
+        r#"{"Attachments":null,"Bcc":["jack@example.com"],"Cc":[{"Email":"bob@example.com","Name":null}],"From":{"Email":"john@example.com","Name":"John Doe"},"HTML":"","Headers":null,"ReplyTo":null,"Subject":"","Tags":[],"Text":"","To":[{"Email":"jane@example.com","Name":null}]}"#,
+        serde_json::to_string(&message).unwrap()
+    );
+}
+
+#[test]
+fn send_message_builder_single_address_success() {
+    let message = SendMessage::builder(
+        "John Doe <john@example.com>"
+            .parse::<AddressObject>()
+            .unwrap(),
+    )
+    .to("jane@example.com".parse::<AddressObject>().unwrap())
+    .cc("Bob <bob@example.com>".parse::<AddressObject>().unwrap())
+    .reply_to(
+        "Secretary <sec@example.com>"
+            .parse::<AddressObject>()
+            .unwrap(),
+    )
+    .build();
+
+    assert_eq!(Some("John Doe".to_string()), message.from.name);
+    assert_eq!("jane@example.com", message.to[0].address);
+    assert_eq!("bob@example.com", message.cc.unwrap()[0].address);
+    assert_eq!("sec@example.com", message.reply_to.unwrap()[0].address);
+}
+
+#[test]
+fn send_message_builder_address_shorthands_success() {
+    let message = SendMessage::builder("john@example.com")
+        .to_address(
+            "Jane Doe <jane@example.com>"
+                .parse::<AddressObject>()
+                .unwrap(),
+        )
+        .to_address("joe@example.com")
+        .cc_address(("bob@example.com", "Bob"))
+        .reply_to_address(
+            "Secretary <sec@example.com>"
+                .parse::<AddressObject>()
+                .unwrap(),
+        )
+        .build();
+
+    assert_str_eq!(
+        r#"{"Attachments":null,"Bcc":null,"Cc":[{"Email":"bob@example.com","Name":"Bob"}],"From":{"Email":"john@example.com","Name":null},"HTML":"","Headers":null,"ReplyTo":[{"Email":"sec@example.com","Name":"Secretary"}],"Subject":"","Tags":[],"Text":"","To":[{"Email":"jane@example.com","Name":"Jane Doe"},{"Email":"joe@example.com","Name":null}]}"#,
+        serde_json::to_string(&message).unwrap()
+    );
+}
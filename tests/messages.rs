@@ -1,8 +1,16 @@
+use std::{cmp::Ordering, time::Duration};
+
+use chrono::{DateTime, TimeZone, Utc};
+use futures_util::TryStreamExt;
 use httpmock::{
     Method::{DELETE, GET, PUT},
-    MockServer,
+    Mock, MockServer,
+};
+use mailpit_client::{
+    MailpitClient,
+    error::Error,
+    models::{BulkMode, MessageInfo, MessageSummary, MessagesSummary, PollConfig},
 };
-use mailpit_client::{MailpitClient, models::MessagesSummary};
 use pretty_assertions::assert_eq;
 
 #[tokio::test]
@@ -81,9 +89,47 @@ async fn get_list_messages_success() {
     mock.assert();
 }
 
+#[tokio::test]
+async fn get_list_messages_deserialize_failure() {
+    let response = r#"{
+      "messages": [],
+      "messages_count": "zero",
+      "messages_unread": 0,
+      "start": 0,
+      "tags": [],
+      "total": 0,
+      "unread": 0
+    }"#;
+
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/messages");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(response);
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let error = client.get_list_messages(None, None).await.unwrap_err();
+
+    let Error::Deserialize {
+        path, body_snippet, ..
+    } = error
+    else {
+        panic!("expected a deserialize error, got {error:?}");
+    };
+    assert_eq!("messages_count", path);
+    assert_eq!(response, body_snippet);
+
+    mock.assert();
+}
+
 #[tokio::test]
 async fn put_set_read_status_success() {
-    let expected_request = r#"{"IDs":["4oRBnPtCXgAqZniRhzLNmS","hXayS6wnCgNnt6aFTvmOF6"],"Read":true,"Search":"tag:backups"}"#;
+    let expected_request =
+        r#"{"IDs":["4oRBnPtCXgAqZniRhzLNmS","hXayS6wnCgNnt6aFTvmOF6"],"Read":true,"Search":null}"#;
 
     let server = MockServer::start_async().await;
     let mock = server
@@ -102,9 +148,67 @@ async fn put_set_read_status_success() {
         .put_set_read_status(
             Some(true),
             Some(&["4oRBnPtCXgAqZniRhzLNmS", "hXayS6wnCgNnt6aFTvmOF6"]),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert!(response);
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn put_set_read_status_ids_and_search_failure() {
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(PUT).path("/api/v1/messages");
+            then.status(200).body("ok");
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let response = client
+        .put_set_read_status(
+            Some(true),
+            Some(&["4oRBnPtCXgAqZniRhzLNmS"]),
             Some("tag:backups"),
             None,
         )
+        .await;
+
+    assert!(matches!(response, Err(Error::InvalidRequest(_))));
+
+    mock.assert_calls(0);
+}
+
+#[tokio::test]
+async fn put_set_read_status_with_tz_success() {
+    let expected_request = r#"{"IDs":null,"Read":false,"Search":"tag:backups"}"#;
+
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(PUT)
+                .path("/api/v1/messages")
+                .query_param("tz", "Europe/Berlin")
+                .body(expected_request);
+            then.status(200)
+                .header("content-type", "application/json")
+                .body("ok");
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let response = client
+        .put_set_read_status(
+            Some(false),
+            None,
+            Some("tag:backups"),
+            Some(chrono_tz::Europe::Berlin),
+        )
         .await
         .unwrap();
 
@@ -209,7 +313,7 @@ async fn get_search_messages_success() {
                 .query_param("query", expected_query)
                 .query_param("start", expected_start.to_string())
                 .query_param("limit", expected_limit.to_string())
-                .query_param("tz", expected_tz.to_string());
+                .query_param("tz", "Europe/Berlin");
             then.status(200)
                 .header("content-type", "application/json")
                 .body(expected_response);
@@ -243,7 +347,7 @@ async fn delete_messages_by_search_success() {
             when.method(DELETE)
                 .path("/api/v1/search")
                 .query_param("query", expected_query)
-                .query_param("tz", expected_tz.to_string());
+                .query_param("tz", "Europe/Berlin");
             then.status(200)
                 .header("content-type", "application/json")
                 .body("ok");
@@ -260,3 +364,1146 @@ async fn delete_messages_by_search_success() {
 
     mock.assert();
 }
+
+#[tokio::test]
+async fn search_messages_stream_success() {
+    let expected_query = "tag:backups";
+    let expected_tz = chrono_tz::Europe::Berlin;
+
+    let server = MockServer::start_async().await;
+    let first_page = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/search")
+                .query_param("query", expected_query)
+                .query_param("start", "0")
+                .query_param("limit", "2")
+                .query_param("tz", "Europe/Berlin");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&["id-1", "id-2"], 3, 0));
+        })
+        .await;
+    let second_page = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/search")
+                .query_param("query", expected_query)
+                .query_param("start", "2")
+                .query_param("limit", "2")
+                .query_param("tz", "Europe/Berlin");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&["id-3"], 3, 2));
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let messages: Vec<_> = client
+        .search_messages_stream(expected_query, 2, Some(expected_tz))
+        .try_collect()
+        .await
+        .unwrap();
+
+    let ids: Vec<_> = messages.iter().map(|m| m.id()).collect();
+    assert_eq!(vec!["id-1", "id-2", "id-3"], ids);
+
+    first_page.assert();
+    second_page.assert();
+}
+
+fn messages_summary(ids: &[&str], messages_count: usize, start: usize) -> String {
+    let messages = ids
+        .iter()
+        .map(|id| {
+            format!(
+                r#"{{
+                  "Attachments": 0,
+                  "Created": "1970-01-01T00:00:00.000Z",
+                  "From": {{ "Address": "string", "Name": "string" }},
+                  "ID": "{id}",
+                  "MessageID": "string",
+                  "Read": false,
+                  "ReplyTo": [],
+                  "Size": 0,
+                  "Snippet": "string",
+                  "Subject": "string",
+                  "Tags": [],
+                  "To": [],
+                  "Username": "string"
+                }}"#
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+
+    format!(
+        r#"{{
+          "messages": [{messages}],
+          "messages_count": {messages_count},
+          "messages_unread": 0,
+          "start": {start},
+          "tags": [],
+          "total": {messages_count},
+          "unread": 0
+        }}"#
+    )
+}
+
+#[tokio::test]
+async fn get_messages_from_and_with_subject_success() {
+    let server = MockServer::start_async().await;
+    let from_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/search")
+                .query_param("query", "from:jane+tests@example.com")
+                .query_param("limit", "10");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&["id-1"], 1, 0));
+        })
+        .await;
+    let subject_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/search")
+                .query_param("query", r#"subject:"Weekly report""#);
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&["id-2"], 1, 0));
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let from = client
+        .get_messages_from("jane+tests@example.com", None, Some(10), None)
+        .await
+        .unwrap();
+    let subject = client
+        .get_messages_with_subject("Weekly report", None, None, None)
+        .await
+        .unwrap();
+
+    assert_eq!("id-1", from.messages[0].id());
+    assert_eq!("id-2", subject.messages[0].id());
+
+    from_mock.assert();
+    subject_mock.assert();
+}
+
+#[tokio::test]
+async fn get_messages_with_subject_quote_failure() {
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/search");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&[], 0, 0));
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let result = client
+        .get_messages_with_subject(r#"Say "hi""#, None, None, None)
+        .await;
+
+    assert!(matches!(result, Err(Error::InvalidSearchQuery(_))));
+
+    mock.assert_calls(0);
+}
+
+#[tokio::test]
+async fn get_unread_messages_success() {
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/search")
+                .query_param("query", "is:unread")
+                .query_param("start", "10")
+                .query_param("limit", "5");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&["id-1", "id-2"], 12, 10));
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let response = client.get_unread_messages(Some(10), Some(5)).await.unwrap();
+
+    assert_eq!(2, response.messages.len());
+    assert_eq!(10, response.start);
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn get_message_by_message_id_success() {
+    let server = MockServer::start_async().await;
+    let found_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/search")
+                .query_param("query", r#"message-id:"id-1@example.com""#);
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&["id-1"], 1, 0));
+        })
+        .await;
+    let missing_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/search")
+                .query_param("query", r#"message-id:"missing@example.com""#);
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&[], 0, 0));
+        })
+        .await;
+    mock_messages(&server).await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let found = client
+        .get_message_by_message_id("<id-1@example.com>")
+        .await
+        .unwrap();
+    let missing = client
+        .get_message_by_message_id("missing@example.com")
+        .await
+        .unwrap();
+    let invalid = client.get_message_by_message_id(r#"a"b@example.com"#).await;
+
+    assert_eq!(Some("id-1"), found.as_ref().map(|m| m.id()));
+    assert!(missing.is_none());
+    assert!(matches!(invalid, Err(Error::InvalidSearchQuery(_))));
+
+    found_mock.assert();
+    missing_mock.assert();
+}
+
+#[tokio::test]
+async fn get_message_by_message_id_duplicate_failure() {
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/search")
+                .query_param("query", r#"message-id:"twice@example.com""#);
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&["id-1", "id-2"], 2, 0));
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let result = client.get_message_by_message_id("twice@example.com").await;
+
+    assert!(matches!(
+        result,
+        Err(Error::MatchCount { query, count: 2 }) if query == r#"message-id:"twice@example.com""#
+    ));
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn expect_message_success() {
+    let server = MockServer::start_async().await;
+    let search_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/search")
+                .query_param("query", "to:jane@example.com")
+                .query_param("limit", "2");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&["id-1"], 1, 0));
+        })
+        .await;
+    mock_messages(&server).await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let message = client.expect_message("to:jane@example.com").await.unwrap();
+
+    assert_eq!("id-1", message.id());
+
+    search_mock.assert();
+}
+
+#[tokio::test]
+async fn expect_message_failure() {
+    let server = MockServer::start_async().await;
+    let none_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/search")
+                .query_param("query", "to:nobody@example.com");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&[], 0, 0));
+        })
+        .await;
+    let many_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/search")
+                .query_param("query", "to:everyone@example.com");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&["id-1", "id-2"], 3, 0));
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let none = client.expect_message("to:nobody@example.com").await;
+    let many = client.expect_message("to:everyone@example.com").await;
+
+    assert!(matches!(
+        none,
+        Err(Error::MatchCount { query, count: 0 }) if query == "to:nobody@example.com"
+    ));
+    assert!(matches!(
+        many,
+        Err(Error::MatchCount { query, count: 3 }) if query == "to:everyone@example.com"
+    ));
+
+    none_mock.assert();
+    many_mock.assert();
+}
+
+#[tokio::test]
+async fn delete_messages_by_search_collecting_success() {
+    let expected_query = "tag:backups";
+    let expected_request = r#"{"IDs":["id-1","id-2"]}"#;
+
+    let server = MockServer::start_async().await;
+    let search_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/search")
+                .query_param("query", expected_query)
+                .query_param("start", "0");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&["id-1", "id-2"], 2, 0));
+        })
+        .await;
+    let delete_mock = server
+        .mock_async(|when, then| {
+            when.method(DELETE)
+                .path("/api/v1/messages")
+                .body(expected_request);
+            then.status(200).body("ok");
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let response = client
+        .delete_messages_by_search_collecting(expected_query, None)
+        .await
+        .unwrap();
+
+    assert_eq!(vec!["id-1".to_string(), "id-2".to_string()], response);
+
+    search_mock.assert();
+    delete_mock.assert();
+}
+
+#[tokio::test]
+async fn delete_messages_before_success() {
+    let server = MockServer::start_async().await;
+    let search_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/search")
+                .query_param("query", "before:2025-03-13T15:09:26Z")
+                .query_param_missing("tz");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&["id-1", "id-2"], 2, 0));
+        })
+        .await;
+    let tz_search_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/search")
+                .query_param("tz", "Europe/Berlin")
+                .query_param("query", "before:2025-03-13T16:09:26+01:00");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&["id-3"], 1, 0));
+        })
+        .await;
+    let delete_mock = server
+        .mock_async(|when, then| {
+            when.method(DELETE).path("/api/v1/messages");
+            then.status(200).body("ok");
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let cutoff = Utc.with_ymd_and_hms(2025, 3, 13, 15, 9, 26).unwrap();
+    let deleted = client.delete_messages_before(cutoff, None).await.unwrap();
+    let deleted_tz = client
+        .delete_messages_before(cutoff, Some(chrono_tz::Europe::Berlin))
+        .await
+        .unwrap();
+
+    assert_eq!(2, deleted);
+    assert_eq!(1, deleted_tz);
+
+    search_mock.assert();
+    tz_search_mock.assert();
+    delete_mock.assert_calls(2);
+}
+
+#[tokio::test]
+async fn delete_messages_by_search_collecting_without_matches_success() {
+    let server = MockServer::start_async().await;
+    let search_mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/search");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&[], 0, 0));
+        })
+        .await;
+    let delete_mock = server
+        .mock_async(|when, then| {
+            when.method(DELETE).path("/api/v1/messages");
+            then.status(200).body("ok");
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let response = client
+        .delete_messages_by_search_collecting("tag:backups", None)
+        .await
+        .unwrap();
+
+    assert!(response.is_empty());
+
+    search_mock.assert();
+    delete_mock.assert_calls(0);
+}
+
+#[cfg(feature = "tags")]
+#[tokio::test]
+async fn drain_success() {
+    let server = MockServer::start_async().await;
+    let delete_messages_mock = server
+        .mock_async(|when, then| {
+            when.method(DELETE)
+                .path("/api/v1/messages")
+                .body(r#"{"IDs":[]}"#);
+            then.status(200).body("ok");
+        })
+        .await;
+    let tags_mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/tags");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(r#"["Tag 1"]"#);
+        })
+        .await;
+    let delete_tag_mock = server
+        .mock_async(|when, then| {
+            when.method(DELETE).path("/api/v1/tags/Tag%201");
+            then.status(200).body("ok");
+        })
+        .await;
+    let count_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/messages")
+                .query_param("limit", "0");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&[], 0, 0));
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    client.drain().await.unwrap();
+
+    delete_messages_mock.assert();
+    tags_mock.assert();
+    delete_tag_mock.assert();
+    count_mock.assert();
+}
+
+#[tokio::test]
+async fn delete_messages_unexpected_response_failure() {
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(DELETE).path("/api/v1/messages");
+            then.status(200).body("not ok");
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let error = client
+        .delete_messages(&["4oRBnPtCXgAqZniRhzLNmS"])
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, Error::UnexpectedResponse { body } if body == "not ok"));
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn empty_response_success() {
+    let server = MockServer::start_async().await;
+    let delete_mock = server
+        .mock_async(|when, then| {
+            when.method(DELETE).path("/api/v1/messages");
+            then.status(200);
+        })
+        .await;
+    let list_mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/messages");
+            then.status(200).header("content-type", "application/json");
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let deleted = client
+        .delete_messages(&["4oRBnPtCXgAqZniRhzLNmS"])
+        .await
+        .unwrap();
+    let listed = client.get_list_messages(None, None).await;
+
+    assert!(deleted);
+    assert!(matches!(listed, Err(Error::EmptyResponse)));
+
+    delete_mock.assert();
+    list_mock.assert();
+}
+
+#[test]
+fn message_info_sort_by_date_success() {
+    let mut messages = vec![
+        message_info("id-b", "2025-01-02T00:00:00Z"),
+        message_info("id-c", "2025-01-01T00:00:00Z"),
+        message_info("id-a", "2025-01-02T00:00:00Z"),
+    ];
+
+    MessageInfo::sort_by_date(&mut messages);
+
+    let ids: Vec<_> = messages.iter().map(|m| m.id()).collect();
+    assert_eq!(vec!["id-c", "id-a", "id-b"], ids);
+    assert_eq!(Ordering::Less, messages[0].cmp_by_date(&messages[1]));
+}
+
+#[test]
+fn message_sent_and_received_at_success() {
+    let info = message_info("id-1", "2025-01-02T08:00:05Z");
+    let summary: MessageSummary = serde_json::from_str(
+        &message_summary("id-1").replace("1970-01-01T00:00:00.000Z", "2025-01-02T07:59:00Z"),
+    )
+    .unwrap();
+
+    assert_eq!(
+        "2025-01-02T08:00:05Z".parse::<DateTime<Utc>>().unwrap(),
+        info.received_at()
+    );
+    assert_eq!(
+        "2025-01-02T07:59:00Z".parse::<DateTime<Utc>>().unwrap(),
+        summary.sent_at()
+    );
+}
+
+#[test]
+fn message_info_deref_success() {
+    let message = message_info("id-a", "2025-01-01T00:00:00Z");
+
+    assert_eq!(message.id(), message.id);
+    assert_eq!(message.subject(), message.subject);
+    assert_eq!(message.tags(), &message.tags);
+}
+
+fn message_info(id: &str, created: &str) -> MessageInfo {
+    serde_json::from_str(&format!(
+        r#"{{
+          "Attachments": 0,
+          "Created": "{created}",
+          "From": {{ "Address": "string", "Name": "string" }},
+          "ID": "{id}",
+          "MessageID": "string",
+          "Read": false,
+          "ReplyTo": [],
+          "Size": 0,
+          "Snippet": "string",
+          "Subject": "string",
+          "Tags": [],
+          "To": [],
+          "Username": "string"
+        }}"#
+    ))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn count_messages_success() {
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/messages")
+                .query_param("limit", "0");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(
+                    r#"{
+                      "messages": [],
+                      "messages_count": 3,
+                      "messages_unread": 1,
+                      "start": 0,
+                      "tags": [],
+                      "total": 3,
+                      "unread": 1
+                    }"#,
+                );
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+
+    assert_eq!(3, client.count_messages().await.unwrap());
+    assert_eq!(1, client.count_unread().await.unwrap());
+    assert!(
+        client
+            .get_list_messages(None, Some(0))
+            .await
+            .unwrap()
+            .messages
+            .is_empty()
+    );
+
+    mock.assert_calls(3);
+}
+
+#[test]
+fn message_info_snippet_plain_success() {
+    let mut message = message_info("id-a", "2025-01-01T00:00:00Z");
+    message.snippet = "  Hello <b>World</b>,<br/>how are\n\tyou? 1 < 2".to_string();
+
+    assert_eq!("Hello World, how are you? 1 < 2", message.snippet_plain());
+}
+
+fn message_summary(id: &str) -> String {
+    format!(
+        r#"{{
+          "Attachments": [],
+          "Bcc": [],
+          "Cc": [],
+          "Date": "1970-01-01T00:00:00.000Z",
+          "From": {{ "Address": "jane@example.com", "Name": "Jane Doe" }},
+          "HTML": "",
+          "ID": "{id}",
+          "Inline": [],
+          "ListUnsubscribe": {{ "Errors": "", "Header": "", "HeaderPost": "", "Links": [] }},
+          "MessageID": "{id}@example.com",
+          "ReplyTo": [],
+          "ReturnPath": "",
+          "Size": 0,
+          "Subject": "string",
+          "Tags": [],
+          "Text": "",
+          "To": [],
+          "Username": ""
+        }}"#
+    )
+}
+
+async fn mock_messages(server: &MockServer) {
+    for id in ["id-1", "id-2"] {
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path(format!("/api/v1/message/{id}"));
+                then.status(200)
+                    .header("content-type", "application/json")
+                    .body(message_summary(id));
+            })
+            .await;
+    }
+    server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/message/missing");
+            then.status(404).body("message not found");
+        })
+        .await;
+}
+
+#[tokio::test]
+async fn get_list_messages_chronological_success() {
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/messages")
+                .query_param("start", "2")
+                .query_param("limit", "3");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&["id-3", "id-2", "id-1"], 5, 2));
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let response = client
+        .get_list_messages_chronological(Some(2), Some(3))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        vec!["id-1", "id-2", "id-3"],
+        response.messages.iter().map(|m| m.id()).collect::<Vec<_>>()
+    );
+    assert_eq!(5, response.messages_count);
+    assert_eq!(2, response.start);
+
+    mock.assert();
+}
+
+/// Mock the headers of message `id`, with a `Message-Id` header if
+/// `message_id` is set.
+async fn mock_headers<'a>(server: &'a MockServer, id: &str, message_id: Option<&str>) -> Mock<'a> {
+    let headers = match message_id {
+        Some(message_id) => {
+            format!(r#"{{"Message-Id": ["<{message_id}>"], "Subject": ["string"]}}"#)
+        }
+        None => r#"{"Subject": ["string"]}"#.to_string(),
+    };
+    server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path(format!("/api/v1/message/{id}/headers"));
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(headers);
+        })
+        .await
+}
+
+#[tokio::test]
+async fn get_messages_by_ids_success() {
+    let server = MockServer::start_async().await;
+    let id_1_mock = mock_headers(&server, "id-1", Some("shared@example.com")).await;
+    let id_3_mock = mock_headers(&server, "id-3", Some("shared@example.com")).await;
+    let missing_mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/message/missing/headers");
+            then.status(404).body("message not found");
+        })
+        .await;
+    let search_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/search")
+                .query_param("query", r#"message-id:"shared@example.com""#)
+                .query_param("limit", "250");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&["id-1", "id-3"], 2, 0));
+        })
+        .await;
+    let list_mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/messages");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&[], 0, 0));
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let messages = client
+        .get_messages_by_ids(&["id-3", "missing", "id-1", "id-3"])
+        .await
+        .unwrap();
+    let none = client.get_messages_by_ids(&[]).await.unwrap();
+
+    assert_eq!(
+        vec!["id-3", "id-1"],
+        messages.iter().map(|m| m.id()).collect::<Vec<_>>()
+    );
+    assert!(none.is_empty());
+
+    id_1_mock.assert();
+    id_3_mock.assert();
+    missing_mock.assert();
+    search_mock.assert_calls(2);
+    list_mock.assert_calls(0);
+}
+
+#[tokio::test]
+async fn get_messages_by_ids_without_message_id_success() {
+    let server = MockServer::start_async().await;
+    let headers_mock = mock_headers(&server, "id-2", None).await;
+    let list_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/messages")
+                .query_param("start", "0")
+                .query_param("limit", "250");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&["id-1", "id-2", "id-3"], 3, 0));
+        })
+        .await;
+    let search_mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/search");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&[], 0, 0));
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let messages = client.get_messages_by_ids(&["id-2"]).await.unwrap();
+
+    assert_eq!(
+        vec!["id-2"],
+        messages.iter().map(|m| m.id()).collect::<Vec<_>>()
+    );
+
+    headers_mock.assert();
+    list_mock.assert();
+    search_mock.assert_calls(0);
+}
+
+#[tokio::test]
+async fn get_message_light_success() {
+    let server = MockServer::start_async().await;
+    let headers_mock = mock_headers(&server, "id-2", Some("id-2@example.com")).await;
+    let missing_mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/message/missing/headers");
+            then.status(404).body("message not found");
+        })
+        .await;
+    let search_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/search")
+                .query_param("query", r#"message-id:"id-2@example.com""#);
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&["id-2"], 1, 0));
+        })
+        .await;
+    let latest_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/messages")
+                .query_param_missing("start")
+                .query_param("limit", "1");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&["id-1"], 2, 0));
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let message = client.get_message_light("id-2").await.unwrap().unwrap();
+    let latest = client.get_message_light("latest").await.unwrap().unwrap();
+    let missing = client.get_message_light("missing").await.unwrap();
+
+    assert_eq!("id-2", message.id());
+    assert_eq!("id-1", latest.id());
+    assert!(missing.is_none());
+
+    headers_mock.assert();
+    missing_mock.assert();
+    search_mock.assert();
+    latest_mock.assert();
+}
+
+#[tokio::test]
+async fn export_messages_ndjson_success() {
+    let server = MockServer::start_async().await;
+    let list_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/messages")
+                .query_param("start", "0")
+                .query_param("limit", "250");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&["id-1", "id-2"], 2, 0));
+        })
+        .await;
+    mock_messages(&server).await;
+    let unread_mock = server
+        .mock_async(|when, then| {
+            when.method(PUT)
+                .path("/api/v1/messages")
+                .json_body_includes(r#"{"Read":false}"#);
+            then.status(200).body("ok");
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let mut output = Vec::new();
+    let count = client.export_messages_ndjson(&mut output).await.unwrap();
+
+    let summaries: Vec<MessageSummary> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(2, count);
+    assert_eq!(
+        vec!["id-1", "id-2"],
+        summaries.iter().map(|s| s.id()).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        serde_json::from_str::<MessageSummary>(&message_summary("id-1")).unwrap(),
+        summaries[0]
+    );
+    assert_eq!("jane@example.com", summaries[0].from().address);
+
+    list_mock.assert();
+    unread_mock.assert_calls(2);
+}
+
+#[tokio::test]
+async fn get_messages_collect_errors_success() {
+    let server = MockServer::start_async().await;
+    mock_messages(&server).await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let outcome = client
+        .get_messages(&["id-1", "missing", "id-2"], BulkMode::CollectErrors)
+        .await
+        .unwrap();
+
+    let ids: Vec<_> = outcome.succeeded.iter().map(|m| m.id()).collect();
+    assert_eq!(vec!["id-1", "id-2"], ids);
+    assert!(!outcome.is_complete());
+    assert_eq!(1, outcome.failed.len());
+    assert!(matches!(
+        &outcome.failed[0],
+        (id, Error::HttpFailure { status: 404, .. }) if id == "missing"
+    ));
+}
+
+#[tokio::test]
+async fn get_messages_fail_fast_failure() {
+    let server = MockServer::start_async().await;
+    mock_messages(&server).await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let result = client
+        .get_messages(&["id-1", "missing", "id-2"], BulkMode::FailFast)
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(Error::HttpFailure { status: 404, .. })
+    ));
+}
+
+#[tokio::test]
+async fn delete_messages_by_searches_collect_errors_success() {
+    let server = MockServer::start_async().await;
+    let ok_mock = server
+        .mock_async(|when, then| {
+            when.method(DELETE)
+                .path("/api/v1/search")
+                .query_param("query", "tag:a");
+            then.status(200).body("ok");
+        })
+        .await;
+    let failure_mock = server
+        .mock_async(|when, then| {
+            when.method(DELETE)
+                .path("/api/v1/search")
+                .query_param("query", "tag:\"b");
+            then.status(400).body("invalid query");
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let outcome = client
+        .delete_messages_by_searches(&["tag:a", "tag:\"b"], None, BulkMode::CollectErrors)
+        .await
+        .unwrap();
+
+    assert_eq!(vec!["tag:a".to_string()], outcome.succeeded);
+    assert_eq!(1, outcome.failed.len());
+    assert_eq!("tag:\"b", outcome.failed[0].0);
+
+    ok_mock.assert();
+    failure_mock.assert();
+}
+
+#[tokio::test]
+async fn wait_until_empty_success() {
+    let server = MockServer::start_async().await;
+    let not_empty_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/messages")
+                .query_param("limit", "0");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&[], 2, 0));
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let (result, empty_mock) = tokio::join!(
+        client.wait_until_empty(PollConfig::with_timeout(Duration::from_secs(5))),
+        async {
+            while not_empty_mock.calls_async().await == 0 {
+                tokio::task::yield_now().await;
+            }
+            not_empty_mock.delete_async().await;
+            server
+                .mock_async(|when, then| {
+                    when.method(GET)
+                        .path("/api/v1/messages")
+                        .query_param("limit", "0");
+                    then.status(200)
+                        .header("content-type", "application/json")
+                        .body(messages_summary(&[], 0, 0));
+                })
+                .await
+        }
+    );
+
+    result.unwrap();
+    empty_mock.assert();
+}
+
+#[tokio::test]
+async fn wait_for_read_status_success() {
+    let server = MockServer::start_async().await;
+    mock_headers(&server, "id-1", Some("id-1@example.com")).await;
+    let unread_mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/search");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&["id-1"], 1, 0));
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let poll = PollConfig {
+        interval: Duration::from_millis(10),
+        ..PollConfig::with_timeout(Duration::from_secs(5))
+    };
+    let (result, read_mock) =
+        tokio::join!(client.wait_for_read_status("id-1", true, poll), async {
+            while unread_mock.calls_async().await < 2 {
+                tokio::task::yield_now().await;
+            }
+            unread_mock.delete_async().await;
+            server
+                .mock_async(|when, then| {
+                    when.method(GET).path("/api/v1/search");
+                    then.status(200)
+                        .header("content-type", "application/json")
+                        .body(
+                            messages_summary(&["id-1"], 1, 0)
+                                .replace(r#""Read": false"#, r#""Read": true"#),
+                        );
+                })
+                .await
+        });
+
+    result.unwrap();
+    read_mock.assert();
+}
+
+#[tokio::test]
+async fn wait_for_read_status_timeout_failure() {
+    let server = MockServer::start_async().await;
+    mock_headers(&server, "id-1", Some("id-1@example.com")).await;
+    server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/search");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&["id-1"], 1, 0));
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let unread = client
+        .wait_for_read_status("id-1", false, PollConfig::default())
+        .await;
+    let result = client
+        .wait_for_read_status(
+            "id-1",
+            true,
+            PollConfig::with_timeout(Duration::from_millis(250)),
+        )
+        .await;
+
+    unread.unwrap();
+    assert!(matches!(result, Err(Error::Timeout(_))));
+}
+
+#[tokio::test]
+async fn wait_until_empty_timeout_failure() {
+    let server = MockServer::start_async().await;
+    server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/messages")
+                .query_param("limit", "0");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&[], 2, 0));
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let result = client
+        .wait_until_empty(PollConfig::with_timeout(Duration::from_millis(250)))
+        .await;
+
+    assert!(matches!(result, Err(Error::Timeout(_))));
+}
+
+#[tokio::test]
+async fn wait_until_empty_interval_success() {
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/messages")
+                .query_param("limit", "0");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(messages_summary(&[], 2, 0));
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let poll = PollConfig {
+        interval: Duration::from_millis(200),
+        jitter: Duration::from_millis(10),
+        timeout: Duration::from_secs(1),
+    };
+    let result = client.wait_until_empty(poll).await;
+
+    assert!(matches!(result, Err(Error::Timeout(_))));
+    // One poll right away and one every ~200ms within the 1s window.
+    let calls = mock.calls_async().await;
+    assert!(
+        (4..=7).contains(&calls),
+        "unexpected number of polls {calls}"
+    );
+}
@@ -1,6 +1,7 @@
 use httpmock::{Method::GET, MockServer};
 use mailpit_client::{
     MailpitClient,
+    error::Error,
     models::{HtmlCheckResponse, LinkCheckResponse, SpamAssassinResponse},
 };
 use pretty_assertions::assert_eq;
@@ -62,7 +63,8 @@ async fn get_html_check_success() {
     let mock = server
         .mock_async(|when, then| {
             when.method(GET)
-                .path("/api/v1/message/database-id/html-check");
+                .path("/api/v1/message/database-id/html-check")
+                .header("accept-language", "de-DE");
             then.status(200)
                 .header("content-type", "application/json")
                 .body(expected_response);
@@ -70,7 +72,10 @@ async fn get_html_check_success() {
         .await;
 
     let client = MailpitClient::new(&server.base_url()).unwrap();
-    let response = client.get_html_check("database-id").await.unwrap();
+    let response = client
+        .get_html_check("database-id", Some("de-DE"))
+        .await
+        .unwrap();
 
     let expected_response: HtmlCheckResponse = serde_json::from_str(expected_response).unwrap();
     assert_eq!(&expected_response, &response);
@@ -111,6 +116,7 @@ async fn get_link_check_success() {
 
     let expected_response: LinkCheckResponse = serde_json::from_str(expected_response).unwrap();
     assert_eq!(&expected_response, &response);
+    assert_eq!(None, response.links[0].status_code_typed());
 
     mock.assert();
 }
@@ -149,3 +155,332 @@ async fn get_spam_assassin_check_success() {
 
     mock.assert();
 }
+
+#[tokio::test]
+async fn get_checks_opt_disabled_success() {
+    let server = MockServer::start_async().await;
+    let spam_assassin_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/message/database-id/sa-check");
+            then.status(400).body("SpamAssassin is not enabled");
+        })
+        .await;
+    let html_check_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/message/database-id/html-check");
+            then.status(400).body("HTML check is disabled");
+        })
+        .await;
+    let missing_mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/message/missing/sa-check");
+            then.status(404).body("message not found");
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let spam_assassin = client
+        .get_spam_assassin_check_opt("database-id")
+        .await
+        .unwrap();
+    let html_check = client
+        .get_html_check_opt("database-id", None)
+        .await
+        .unwrap();
+    let missing = client.get_spam_assassin_check_opt("missing").await;
+
+    assert_eq!(None, spam_assassin);
+    assert_eq!(None, html_check);
+    let missing = missing.unwrap_err();
+    assert!(matches!(missing, Error::HttpFailure { status: 404, .. }));
+    assert!(missing.status_code().unwrap().is_client_error());
+
+    spam_assassin_mock.assert();
+    html_check_mock.assert();
+    missing_mock.assert();
+}
+
+const REPORT_SUMMARY_RESPONSE: &str = r#"{
+  "Attachments": [],
+  "Bcc": [],
+  "Cc": [],
+  "Date": "1970-01-01T00:00:00.000Z",
+  "From": { "Address": "jane@example.com", "Name": "Jane Doe" },
+  "HTML": "<p>Hello</p>",
+  "ID": "database-id",
+  "Inline": [],
+  "ListUnsubscribe": { "Errors": "", "Header": "", "HeaderPost": "", "Links": [] },
+  "MessageID": "database-id@example.com",
+  "ReplyTo": [],
+  "ReturnPath": "",
+  "Size": 0,
+  "Subject": "Hello",
+  "Tags": [],
+  "Text": "Hello",
+  "To": [],
+  "Username": ""
+}"#;
+
+#[tokio::test]
+async fn get_message_report_success() {
+    let summary_response = REPORT_SUMMARY_RESPONSE;
+    let html_check_response = html_check_json(&[("css-flex", 95.5)]);
+    let link_check_response = r#"{
+      "Errors": 0,
+      "Links": []
+    }"#;
+
+    let server = MockServer::start_async().await;
+    let mut mocks = Vec::new();
+    for (path, body) in [
+        ("/api/v1/message/database-id", summary_response),
+        (
+            "/api/v1/message/database-id/html-check",
+            html_check_response.as_str(),
+        ),
+        (
+            "/api/v1/message/database-id/link-check",
+            link_check_response,
+        ),
+    ] {
+        let mock = server
+            .mock_async(|when, then| {
+                when.method(GET).path(path);
+                then.status(200)
+                    .header("content-type", "application/json")
+                    .body(body);
+            })
+            .await;
+        mocks.push(mock);
+    }
+    let spam_assassin_mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/message/database-id/sa-check");
+            then.status(400).body("SpamAssassin is not enabled");
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let report = client.get_message_report("database-id").await.unwrap();
+
+    assert_eq!("Hello", report.summary.subject());
+    assert_eq!(
+        Some(serde_json::from_str::<HtmlCheckResponse>(&html_check_response).unwrap()),
+        report.html_check
+    );
+    assert_eq!(
+        Some(serde_json::from_str::<LinkCheckResponse>(link_check_response).unwrap()),
+        report.link_check
+    );
+    assert_eq!(None, report.spam_assassin_check);
+
+    for mock in mocks {
+        mock.assert();
+    }
+    spam_assassin_mock.assert();
+}
+
+#[tokio::test]
+async fn get_message_report_latest_success() {
+    let server = MockServer::start_async().await;
+    let mut mocks = Vec::new();
+    for (path, body) in [
+        ("/api/v1/message/latest", REPORT_SUMMARY_RESPONSE),
+        (
+            "/api/v1/message/database-id/html-check",
+            &html_check_json(&[]),
+        ),
+        (
+            "/api/v1/message/database-id/link-check",
+            r#"{ "Errors": 0, "Links": [] }"#,
+        ),
+        (
+            "/api/v1/message/database-id/sa-check",
+            r#"{ "Error": "", "IsSpam": false, "Score": 0, "Rules": [] }"#,
+        ),
+    ] {
+        let mock = server
+            .mock_async(|when, then| {
+                when.method(GET).path(path);
+                then.status(200)
+                    .header("content-type", "application/json")
+                    .body(body);
+            })
+            .await;
+        mocks.push(mock);
+    }
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let report = client.get_message_report("latest").await.unwrap();
+
+    assert_eq!("database-id", report.summary.id());
+    assert!(report.html_check.is_some());
+    assert!(report.link_check.is_some());
+    assert!(report.spam_assassin_check.is_some());
+
+    for mock in mocks {
+        mock.assert();
+    }
+}
+
+#[tokio::test]
+async fn get_message_report_check_failure() {
+    for (status, body) in [(404, "message not found"), (500, "SpamAssassin crashed")] {
+        let server = MockServer::start_async().await;
+        let mut mocks = Vec::new();
+        for (path, body) in [
+            ("/api/v1/message/database-id", REPORT_SUMMARY_RESPONSE),
+            (
+                "/api/v1/message/database-id/html-check",
+                &html_check_json(&[]),
+            ),
+            (
+                "/api/v1/message/database-id/link-check",
+                r#"{ "Errors": 0, "Links": [] }"#,
+            ),
+        ] {
+            let mock = server
+                .mock_async(|when, then| {
+                    when.method(GET).path(path);
+                    then.status(200)
+                        .header("content-type", "application/json")
+                        .body(body);
+                })
+                .await;
+            mocks.push(mock);
+        }
+        let spam_assassin_mock = server
+            .mock_async(|when, then| {
+                when.method(GET)
+                    .path("/api/v1/message/database-id/sa-check");
+                then.status(status).body(body);
+            })
+            .await;
+
+        let client = MailpitClient::new(&server.base_url()).unwrap();
+        let error = client.get_message_report("database-id").await.unwrap_err();
+
+        assert!(matches!(error, Error::HttpFailure { status: s, .. } if s == status));
+
+        for mock in mocks {
+            mock.assert();
+        }
+        spam_assassin_mock.assert();
+    }
+}
+
+#[cfg(feature = "simd-json")]
+#[tokio::test]
+async fn get_html_check_simd_json_success() {
+    let slugs: Vec<_> = (0..500).map(|i| format!("feature-{i}")).collect();
+    let warnings: Vec<_> = slugs
+        .iter()
+        .enumerate()
+        .map(|(i, slug)| (slug.as_str(), (i % 100) as f32 + 0.5))
+        .collect();
+    let expected_response = html_check_json(&warnings);
+
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/api/v1/message/database-id/html-check");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(&expected_response);
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let response = client.get_html_check("database-id", None).await.unwrap();
+
+    let expected_response: HtmlCheckResponse = serde_json::from_str(&expected_response).unwrap();
+    assert_eq!(&expected_response, &response);
+
+    mock.assert();
+}
+
+#[test]
+fn html_check_warnings_below_success() {
+    let response =
+        html_check_response(&[("css-flex", 95.5), ("html-svg", 42.0), ("css-grid", 79.9)]);
+
+    let below: Vec<_> = response
+        .warnings_below(80.0)
+        .into_iter()
+        .map(|w| w.slug.as_str())
+        .collect();
+
+    assert_eq!(vec!["html-svg", "css-grid"], below);
+    assert!(response.warnings_below(0.0).is_empty());
+}
+
+#[test]
+fn html_warning_caniemail_url_success() {
+    let mut response = html_check_response(&[("css-flex", 95.5), ("html-svg", 42.0)]);
+    response.warnings[1].url = "not a url".to_string();
+
+    let warning = &response.warnings[0];
+    assert_eq!("css-flex", warning.feature_slug());
+    assert_eq!(
+        Some("https://www.caniemail.com/features/css-flex/"),
+        warning.caniemail_url().as_ref().map(|url| url.as_str())
+    );
+    assert_eq!(None, response.warnings[1].caniemail_url());
+}
+
+#[test]
+fn html_check_worst_supported_success() {
+    let response =
+        html_check_response(&[("css-flex", 95.5), ("html-svg", 42.0), ("css-grid", 79.9)]);
+
+    assert_eq!(
+        Some("html-svg"),
+        response.worst_supported().map(|w| w.slug.as_str())
+    );
+    assert_eq!(None, html_check_response(&[]).worst_supported());
+}
+
+fn html_check_response(warnings: &[(&str, f32)]) -> HtmlCheckResponse {
+    serde_json::from_str(&html_check_json(warnings)).unwrap()
+}
+
+fn html_check_json(warnings: &[(&str, f32)]) -> String {
+    let warnings = warnings
+        .iter()
+        .map(|(slug, supported)| {
+            format!(
+                r#"{{
+                  "Category": "css",
+                  "Description": "string",
+                  "Keywords": "string",
+                  "NotesByNumber": {{}},
+                  "Results": [],
+                  "Score": {{
+                    "Found": 1,
+                    "Partial": 0,
+                    "Supported": {supported},
+                    "Unsupported": {unsupported}
+                  }},
+                  "Slug": "{slug}",
+                  "Tags": [],
+                  "Title": "string",
+                  "URL": "https://www.caniemail.com/features/{slug}/"
+                }}"#,
+                unsupported = 100.0 - supported
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+
+    format!(
+        r#"{{
+          "Platforms": {{}},
+          "Total": {{ "Nodes": 0, "Partial": 0, "Supported": 0, "Tests": 0, "Unsupported": 0 }},
+          "Warnings": [{warnings}]
+        }}"#
+    )
+}
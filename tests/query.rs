@@ -0,0 +1,265 @@
+use httpmock::{
+    HttpMockRequest,
+    Method::{self, DELETE, GET, PUT},
+    Mock, MockServer,
+};
+use mailpit_client::{MailpitClient, Tz, models::EmbedMode};
+
+const MESSAGES_SUMMARY: &str = r#"{
+  "messages": [],
+  "messages_count": 0,
+  "messages_unread": 0,
+  "start": 0,
+  "tags": [],
+  "total": 0,
+  "unread": 0
+}"#;
+
+/// Mock `path` only matching if the query string is exactly `query`.
+async fn mock_query<'a>(
+    server: &'a MockServer,
+    method: Method,
+    path: &str,
+    query: Option<&'static str>,
+    body: &str,
+) -> Mock<'a> {
+    server
+        .mock_async(|when, then| {
+            when.method(method)
+                .path(path)
+                .is_true(move |req: &HttpMockRequest| req.uri().query() == query);
+            then.status(200).body(body);
+        })
+        .await
+}
+
+#[tokio::test]
+async fn get_list_messages_query_success() {
+    let server = MockServer::start_async().await;
+    let paged_mock = mock_query(
+        &server,
+        GET,
+        "/api/v1/messages",
+        Some("start=10&limit=0"),
+        MESSAGES_SUMMARY,
+    )
+    .await;
+    let plain_mock = mock_query(&server, GET, "/api/v1/messages", None, MESSAGES_SUMMARY).await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    client.get_list_messages(Some(10), Some(0)).await.unwrap();
+    client.get_list_messages(None, None).await.unwrap();
+
+    paged_mock.assert();
+    plain_mock.assert();
+}
+
+#[tokio::test]
+async fn get_search_messages_query_success() {
+    let server = MockServer::start_async().await;
+    let full_mock = mock_query(
+        &server,
+        GET,
+        "/api/v1/search",
+        Some("query=is%3Aunread+tag%3A%22a+b%22&start=0&limit=25&tz=America%2FNew_York"),
+        MESSAGES_SUMMARY,
+    )
+    .await;
+    let plain_mock = mock_query(
+        &server,
+        GET,
+        "/api/v1/search",
+        Some("query=subject%3Ahello"),
+        MESSAGES_SUMMARY,
+    )
+    .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    client
+        .get_search_messages(
+            r#"is:unread tag:"a b""#,
+            Some(0),
+            Some(25),
+            Some(Tz::America__New_York),
+        )
+        .await
+        .unwrap();
+    client
+        .get_search_messages("subject:hello", None, None, None)
+        .await
+        .unwrap();
+
+    full_mock.assert();
+    plain_mock.assert();
+}
+
+#[tokio::test]
+async fn delete_messages_by_search_query_success() {
+    let server = MockServer::start_async().await;
+    let mock = mock_query(
+        &server,
+        DELETE,
+        "/api/v1/search",
+        Some("query=before%3A2025-01-01&tz=Europe%2FBerlin"),
+        "ok",
+    )
+    .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    client
+        .delete_messages_by_search("before:2025-01-01", Some(Tz::Europe__Berlin))
+        .await
+        .unwrap();
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn put_set_read_status_query_success() {
+    let server = MockServer::start_async().await;
+    let tz_mock = mock_query(
+        &server,
+        PUT,
+        "/api/v1/messages",
+        Some("tz=Europe%2FBerlin"),
+        "ok",
+    )
+    .await;
+    let plain_mock = mock_query(&server, PUT, "/api/v1/messages", None, "ok").await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    client
+        .put_set_read_status(Some(true), None, None, Some(Tz::Europe__Berlin))
+        .await
+        .unwrap();
+    client
+        .put_set_read_status(Some(true), None, None, None)
+        .await
+        .unwrap();
+
+    tz_mock.assert();
+    plain_mock.assert();
+}
+
+#[tokio::test]
+async fn get_link_check_query_success() {
+    let body = r#"{ "Errors": 0, "Links": [] }"#;
+
+    let server = MockServer::start_async().await;
+    let follow_mock = mock_query(
+        &server,
+        GET,
+        "/api/v1/message/database-id/link-check",
+        Some("follow=false"),
+        body,
+    )
+    .await;
+    let plain_mock = mock_query(
+        &server,
+        GET,
+        "/api/v1/message/latest/link-check",
+        None,
+        body,
+    )
+    .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    client
+        .get_link_check("database-id", Some(false))
+        .await
+        .unwrap();
+    client.get_link_check("latest", None).await.unwrap();
+
+    follow_mock.assert();
+    plain_mock.assert();
+}
+
+#[tokio::test]
+async fn get_render_message_html_part_query_success() {
+    let server = MockServer::start_async().await;
+    let embed_mock = mock_query(
+        &server,
+        GET,
+        "/view/database-id.html",
+        Some("embed=1"),
+        "<html></html>",
+    )
+    .await;
+    let plain_mock = mock_query(&server, GET, "/view/latest.html", None, "<html></html>").await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    client
+        .get_render_message_html_part("database-id", Some(EmbedMode::Embedded))
+        .await
+        .unwrap();
+    client
+        .get_render_message_html_part("latest", None)
+        .await
+        .unwrap();
+
+    embed_mock.assert();
+    plain_mock.assert();
+}
+
+#[tokio::test]
+async fn default_tz_query_success() {
+    let server = MockServer::start_async().await;
+    let search_mock = mock_query(
+        &server,
+        GET,
+        "/api/v1/search",
+        Some("query=after%3A2025-01-01&tz=Europe%2FBerlin"),
+        MESSAGES_SUMMARY,
+    )
+    .await;
+    let override_mock = mock_query(
+        &server,
+        GET,
+        "/api/v1/search",
+        Some("query=after%3A2025-01-01&tz=America%2FNew_York"),
+        MESSAGES_SUMMARY,
+    )
+    .await;
+    let delete_mock = mock_query(
+        &server,
+        DELETE,
+        "/api/v1/search",
+        Some("query=after%3A2025-01-01&tz=Europe%2FBerlin"),
+        "ok",
+    )
+    .await;
+    let read_mock = mock_query(
+        &server,
+        PUT,
+        "/api/v1/messages",
+        Some("tz=Europe%2FBerlin"),
+        "ok",
+    )
+    .await;
+
+    let client = MailpitClient::builder(&server.base_url())
+        .default_tz(Tz::Europe__Berlin)
+        .build()
+        .unwrap();
+    client
+        .get_search_messages("after:2025-01-01", None, None, None)
+        .await
+        .unwrap();
+    client
+        .get_search_messages("after:2025-01-01", None, None, Some(Tz::America__New_York))
+        .await
+        .unwrap();
+    client
+        .delete_messages_by_search("after:2025-01-01", None)
+        .await
+        .unwrap();
+    client
+        .put_set_read_status(Some(true), None, None, None)
+        .await
+        .unwrap();
+
+    search_mock.assert();
+    override_mock.assert();
+    delete_mock.assert();
+    read_mock.assert();
+}
@@ -0,0 +1,152 @@
+use std::time::Duration;
+
+use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+use mailpit_client::{
+    error::Error,
+    search::{SearchQuery, SearchTerm},
+};
+use pretty_assertions::{assert_eq, assert_str_eq};
+
+#[test]
+fn search_query_build_success() {
+    let query = SearchQuery::new()
+        .tag("Tag 1")
+        .from("john@example.com")
+        .subject("Weekly report")
+        .is_read(false)
+        .has_attachment()
+        .text("invoice");
+
+    assert_str_eq!(
+        r#"tag:"Tag 1" from:john@example.com subject:"Weekly report" is:unread has:attachment invoice"#,
+        query.build()
+    );
+}
+
+#[test]
+fn search_query_parse_success() {
+    let query = SearchQuery::parse(
+        r#"tag:backups to:jane@example.com  subject:"Hello World" is:read has:attachment "free text" before:2025-01-01 foo"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        &[
+            SearchTerm::Tag("backups".to_string()),
+            SearchTerm::To("jane@example.com".to_string()),
+            SearchTerm::Subject("Hello World".to_string()),
+            SearchTerm::Read(true),
+            SearchTerm::HasAttachment,
+            SearchTerm::Text("free text".to_string()),
+            SearchTerm::Before("2025-01-01".to_string()),
+            SearchTerm::Text("foo".to_string()),
+        ],
+        query.terms()
+    );
+}
+
+#[test]
+fn search_query_round_trip_success() {
+    let search = r#"tag:"Tag 1" from:john@example.com to:jane@example.com subject:"Hello World" is:unread has:attachment !tag:spam "free text" foo"#;
+
+    let query = SearchQuery::parse(search).unwrap();
+    assert_str_eq!(search, query.build());
+    assert_eq!(query, SearchQuery::parse(&query.build()).unwrap());
+}
+
+#[test]
+fn search_query_parse_failure() {
+    assert!(matches!(
+        SearchQuery::parse(r#"subject:"Hello World"#),
+        Err(Error::InvalidSearchQuery(_))
+    ));
+    assert!(matches!(
+        SearchQuery::parse("tag:"),
+        Err(Error::InvalidSearchQuery(_))
+    ));
+}
+
+#[test]
+fn search_query_negation_success() {
+    let query = SearchQuery::new()
+        .text("-draft")
+        .subject("!important")
+        .from("-bounce@example.com");
+
+    assert_str_eq!(
+        r#""-draft" subject:"!important" from:"-bounce@example.com""#,
+        query.build()
+    );
+    assert_eq!(query, SearchQuery::parse(&query.build()).unwrap());
+    assert_eq!(
+        vec![SearchTerm::Raw("-draft".to_string())],
+        SearchQuery::parse("-draft").unwrap().terms()
+    );
+}
+
+#[test]
+fn search_query_try_build_failure() {
+    let query = SearchQuery::new().subject(r#"Say "hi""#);
+
+    assert!(matches!(
+        query.try_build(),
+        Err(Error::InvalidSearchQuery(_))
+    ));
+    assert_str_eq!(
+        "subject:Weekly",
+        SearchQuery::new().subject("Weekly").try_build().unwrap()
+    );
+}
+
+#[test]
+fn search_query_since_success() {
+    let now = Utc.with_ymd_and_hms(2025, 3, 14, 15, 9, 26).unwrap();
+
+    let query = SearchQuery::new()
+        .tag("backups")
+        .since(now - TimeDelta::hours(1));
+
+    assert_str_eq!("tag:backups after:2025-03-14T14:09:26Z", query.build());
+    assert_eq!(query, SearchQuery::parse(&query.build()).unwrap());
+}
+
+#[test]
+fn search_query_within_last_success() {
+    let now = Utc.with_ymd_and_hms(2025, 3, 14, 15, 9, 26).unwrap();
+
+    let query = SearchQuery::new()
+        .tag("backups")
+        .within_last_at(now, Duration::from_secs(60 * 60));
+    let saturated = SearchQuery::new().within_last_at(now, Duration::MAX);
+
+    assert_str_eq!("tag:backups after:2025-03-14T14:09:26Z", query.build());
+    assert_eq!(
+        SearchQuery::new().since(DateTime::<Utc>::MIN_UTC),
+        saturated
+    );
+}
+
+#[test]
+fn search_query_before_success() {
+    let now = Utc.with_ymd_and_hms(2025, 3, 14, 15, 9, 26).unwrap();
+    let age = Duration::from_secs(36 * 60 * 60);
+
+    let query = SearchQuery::new().before(now - TimeDelta::from_std(age).unwrap());
+
+    assert_str_eq!("before:2025-03-13T03:09:26Z", query.build());
+    assert_eq!(query, SearchQuery::parse(&query.build()).unwrap());
+}
+
+#[test]
+fn search_query_older_than_success() {
+    let now = Utc.with_ymd_and_hms(2025, 3, 14, 15, 9, 26).unwrap();
+    let age = Duration::from_secs(36 * 60 * 60);
+
+    let query = SearchQuery::new().older_than_at(now, age);
+    let berlin = SearchQuery::new().before(
+        (now - TimeDelta::from_std(age).unwrap()).with_timezone(&chrono_tz::Europe::Berlin),
+    );
+
+    assert_str_eq!("before:2025-03-13T03:09:26Z", query.build());
+    assert_str_eq!("before:2025-03-13T04:09:26+01:00", berlin.build());
+}
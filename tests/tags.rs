@@ -2,7 +2,7 @@ use httpmock::{
     Method::{DELETE, GET, PUT},
     MockServer,
 };
-use mailpit_client::MailpitClient;
+use mailpit_client::{MailpitClient, error::Error};
 use pretty_assertions::assert_eq;
 
 #[tokio::test]
@@ -31,6 +31,64 @@ async fn get_all_current_tags_success() {
     mock.assert();
 }
 
+#[tokio::test]
+async fn get_tags_with_counts_success() {
+    let info_response = r#"{
+      "Database": "string",
+      "DatabaseSize": 0,
+      "LatestVersion": "string",
+      "Messages": 7,
+      "RuntimeStats": {
+        "Memory": 0,
+        "MessagesDeleted": 0,
+        "SMTPAccepted": 0,
+        "SMTPAcceptedSize": 0,
+        "SMTPIgnored": 0,
+        "SMTPRejected": 0,
+        "Uptime": 0
+      },
+      "Tags": {
+        "Tag 1": 2,
+        "Tag 2": 5,
+        "Tag 3": 2
+      },
+      "Unread": 0,
+      "Version": "string"
+    }"#;
+
+    let server = MockServer::start_async().await;
+    let tags_mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/tags");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(r#"["Tag 1", "Tag 2", "Tag 3", "Tag 4"]"#);
+        })
+        .await;
+    let info_mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/info");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(info_response);
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let response = client.get_tags_with_counts().await.unwrap();
+
+    let expected_response = vec![
+        ("Tag 2".to_string(), 5),
+        ("Tag 1".to_string(), 2),
+        ("Tag 3".to_string(), 2),
+        ("Tag 4".to_string(), 0),
+    ];
+    assert_eq!(&expected_response, &response);
+
+    tags_mock.assert();
+    info_mock.assert();
+}
+
 #[tokio::test]
 async fn put_set_message_tags_success() {
     let expected_request =
@@ -103,3 +161,21 @@ async fn delete_tag_success() {
 
     mock.assert();
 }
+
+#[tokio::test]
+async fn delete_tag_unexpected_response_failure() {
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(DELETE).path("/api/v1/tags/Tag%201");
+            then.status(200).body("not ok");
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let error = client.delete_tag("Tag 1").await.unwrap_err();
+
+    assert!(matches!(error, Error::UnexpectedResponse { body } if body == "not ok"));
+
+    mock.assert();
+}
@@ -1,10 +1,14 @@
 use httpmock::{
+    HttpMockRequest,
     Method::{GET, PUT},
     MockServer,
 };
 use mailpit_client::{
     MailpitClient,
-    models::{ChaosTrigger, ChaosTriggersConfiguration, ChaosTriggersResponse},
+    error::Error,
+    models::{
+        ChaosTrigger, ChaosTriggersConfiguration, ChaosTriggersResponse, EmbedMode, RenderedMessage,
+    },
 };
 use pretty_assertions::{assert_eq, assert_str_eq};
 
@@ -44,6 +48,54 @@ async fn get_chaos_triggers_success() {
     mock.assert();
 }
 
+#[tokio::test]
+async fn chaos_disabled_success() {
+    let webui_response = r#"{
+      "ChaosEnabled": false,
+      "DuplicatesIgnored": false,
+      "HideDeleteAllButton": false,
+      "Label": "",
+      "MessageRelay": {
+        "AllowedRecipients": "",
+        "BlockedRecipients": "",
+        "Enabled": false,
+        "OverrideFrom": "",
+        "PreserveMessageIDs": false,
+        "ReturnPath": "",
+        "SMTPServer": ""
+      },
+      "SpamAssassin": false
+    }"#;
+
+    let server = MockServer::start_async().await;
+    let webui_mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/webui");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(webui_response);
+        })
+        .await;
+    let chaos_mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/chaos");
+            then.status(400).body("Chaos is not enabled");
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+
+    assert!(!client.chaos_enabled().await.unwrap());
+    assert_eq!(None, client.get_chaos_triggers_opt().await.unwrap());
+    assert!(matches!(
+        client.get_chaos_triggers().await,
+        Err(Error::HttpFailure { status: 400, .. })
+    ));
+
+    webui_mock.assert();
+    chaos_mock.assert_calls(2);
+}
+
 #[tokio::test]
 async fn put_set_chaos_triggers_success() {
     let expected_request = r#"{"Authentication":{"ErrorCode":451,"Probability":5},"Recipient":{"ErrorCode":451,"Probability":5},"Sender":{"ErrorCode":451,"Probability":5}}"#;
@@ -83,6 +135,130 @@ async fn put_set_chaos_triggers_success() {
     mock.assert();
 }
 
+#[tokio::test]
+async fn put_set_chaos_triggers_diff_success() {
+    let before_response = r#"{
+      "Authentication": { "ErrorCode": 451, "Probability": 0 },
+      "Recipient": { "ErrorCode": 451, "Probability": 0 },
+      "Sender": { "ErrorCode": 451, "Probability": 0 }
+    }"#;
+    let after_response = r#"{
+      "Authentication": { "ErrorCode": 451, "Probability": 0 },
+      "Recipient": { "ErrorCode": 550, "Probability": 50 },
+      "Sender": { "ErrorCode": 451, "Probability": 0 }
+    }"#;
+
+    let server = MockServer::start_async().await;
+    let get_mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/api/v1/chaos");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(before_response);
+        })
+        .await;
+    let put_mock = server
+        .mock_async(|when, then| {
+            when.method(PUT).path("/api/v1/chaos");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(after_response);
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let config = ChaosTriggersConfiguration {
+        authentication: ChaosTrigger::new(451, 0).unwrap(),
+        recipient: ChaosTrigger::new(550, 50).unwrap(),
+        sender: ChaosTrigger::new(451, 0).unwrap(),
+    };
+    let (before, after) = client
+        .put_set_chaos_triggers_diff(Some(config))
+        .await
+        .unwrap();
+
+    let expected_before: ChaosTriggersResponse = serde_json::from_str(before_response).unwrap();
+    let expected_after: ChaosTriggersResponse = serde_json::from_str(after_response).unwrap();
+    assert_eq!(&expected_before, &before);
+    assert_eq!(&expected_after, &after);
+
+    get_mock.assert();
+    put_mock.assert();
+}
+
+#[tokio::test]
+async fn put_set_chaos_triggers_invalid_failure() {
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.path("/api/v1/chaos");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body("{}");
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let config = || ChaosTriggersConfiguration {
+        authentication: ChaosTrigger {
+            error_code: 451,
+            probability: 5,
+        },
+        recipient: ChaosTrigger {
+            error_code: 700,
+            probability: 5,
+        },
+        sender: ChaosTrigger {
+            error_code: 451,
+            probability: 5,
+        },
+    };
+
+    let error = client
+        .put_set_chaos_triggers(Some(config()))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        Error::InvalidChaosTrigger {
+            error_code: 700,
+            probability: 5
+        }
+    ));
+    let error = client
+        .put_set_chaos_triggers_diff(Some(config()))
+        .await
+        .unwrap_err();
+    assert!(matches!(error, Error::InvalidChaosTrigger { .. }));
+
+    mock.assert_calls(0);
+}
+
+#[test]
+fn chaos_trigger_new_accepts_boundaries() {
+    for (error_code, probability) in [(400, 0), (599, 100), (451, 5)] {
+        let trigger = ChaosTrigger::new(error_code, probability).unwrap();
+        assert_eq!(
+            ChaosTrigger {
+                error_code,
+                probability
+            },
+            trigger
+        );
+    }
+}
+
+#[test]
+fn chaos_trigger_new_rejects_out_of_range() {
+    for (error_code, probability) in [(399, 5), (600, 5), (451, -1), (451, 101)] {
+        let error = ChaosTrigger::new(error_code, probability).unwrap_err();
+        assert!(matches!(
+            error,
+            Error::InvalidChaosTrigger { error_code: c, probability: p } if c == error_code && p == probability
+        ));
+    }
+}
+
 #[tokio::test]
 async fn get_render_message_html_part_success() {
     let expected_response = r#"<div style="text-align:center"><p style="font-family: arial; font-size: 24px;">Mailpit is <b>awesome</b>!</p><p><img src="cid:mailpit-logo"/></p></div>"#;
@@ -101,7 +277,7 @@ async fn get_render_message_html_part_success() {
 
     let client = MailpitClient::new(&server.base_url()).unwrap();
     let response = client
-        .get_render_message_html_part("database-id", Some(false))
+        .get_render_message_html_part("database-id", Some(EmbedMode::Standalone))
         .await
         .unwrap();
 
@@ -110,6 +286,53 @@ async fn get_render_message_html_part_success() {
     mock.assert();
 }
 
+#[tokio::test]
+async fn get_render_message_html_part_embedded_success() {
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.method(GET)
+                .path("/view/database-id.html")
+                .query_param("embed", "1");
+            then.status(200).body("<p>Embedded</p>");
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let response = client
+        .get_render_message_html_part("database-id", Some(true.into()))
+        .await
+        .unwrap();
+
+    assert_eq!(EmbedMode::Embedded, EmbedMode::from(true));
+    assert_str_eq!("<p>Embedded</p>", response);
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn get_render_message_html_part_encoded_id_success() {
+    let server = MockServer::start_async().await;
+    let mock = server
+        .mock_async(|when, then| {
+            when.is_true(|req: &HttpMockRequest| {
+                req.uri().path() == "/view/..%2F..%2Fapi%2Fv1%2Finfo.html"
+            });
+            then.status(200).body("<html></html>");
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let response = client
+        .get_render_message_html_part("../../api/v1/info", None)
+        .await
+        .unwrap();
+
+    assert_str_eq!("<html></html>", &response);
+
+    mock.assert();
+}
+
 #[tokio::test]
 async fn get_render_message_text_part_success() {
     let expected_response = r#"Mailpit is awesome!"#;
@@ -134,3 +357,39 @@ async fn get_render_message_text_part_success() {
 
     mock.assert();
 }
+
+#[tokio::test]
+async fn get_rendered_message_without_html_success() {
+    let expected_text = r#"Mailpit is awesome!"#;
+
+    let server = MockServer::start_async().await;
+    let html_mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/view/database-id.html");
+            then.status(404).body("Not found");
+        })
+        .await;
+    let text_mock = server
+        .mock_async(|when, then| {
+            when.method(GET).path("/view/database-id.txt");
+            then.status(200).body(expected_text);
+        })
+        .await;
+
+    let client = MailpitClient::new(&server.base_url()).unwrap();
+    let response = client
+        .get_rendered_message("database-id", None)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        RenderedMessage {
+            html: None,
+            text: expected_text.to_string(),
+        },
+        response
+    );
+
+    html_mock.assert();
+    text_mock.assert();
+}